@@ -0,0 +1,88 @@
+use atrl_data::prelude::*;
+use bevy::{ecs::system::SystemState, prelude::*};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const CAVE_SIZE: u32 = 128;
+const SEEDS: [u64; 3] = [1, 42, 1337];
+
+/// A generated cave on its own, walls are anything the automata left above the middle value.
+struct Cave {
+    terrain: Grid<u32>,
+}
+
+impl Cave {
+    fn generate(seed: u64) -> Self {
+        let data = MapGenerator::new(
+            UVec2::splat(CAVE_SIZE),
+            Random::new(seed),
+            ScatterBuilder::new(),
+            (),
+        )
+        .with(CellularAutomataBuilder::new())
+        .generate();
+
+        Self {
+            terrain: data.terrain_grid,
+        }
+    }
+
+    fn is_floor(&self, point: UVec2) -> bool {
+        self.terrain.get(point).map_or(false, |&value| value < u32::MAX / 2)
+    }
+
+    fn position(point: UVec2) -> Position {
+        Position::new(
+            WorldPosition::ZERO,
+            LocalPosition::new(point.x, point.y, MapLayer::Terrain as u32),
+        )
+    }
+
+    /// The floor tiles closest to the bottom left and top right corners.
+    fn endpoints(&self) -> (Position, Position) {
+        let floors: Vec<UVec2> = (0..CAVE_SIZE)
+            .flat_map(|y| (0..CAVE_SIZE).map(move |x| UVec2::new(x, y)))
+            .filter(|&point| self.is_floor(point))
+            .collect();
+
+        let start = floors.iter().min_by_key(|point| point.x + point.y).copied().unwrap_or_default();
+        let end = floors.iter().max_by_key(|point| point.x + point.y).copied().unwrap_or_default();
+        (Self::position(start), Self::position(end))
+    }
+}
+
+impl PathProvider for Cave {
+    fn is_walkable(&mut self, position: Position, _: u8, _: &Query<&BlocksMovement>) -> bool {
+        position.get_world_position() == WorldPosition::ZERO &&
+            self.is_floor(position.get_local_position().gridpoint())
+    }
+
+    fn cost(&mut self, _: Position, _: u8) -> u32 { 1 }
+
+    fn has_uniform_cost(&mut self, _: u8) -> bool { true }
+}
+
+fn cave_paths(c: &mut Criterion) {
+    let mut world = World::new();
+    let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+    let q_blocks_movement = state.get(&world);
+
+    let mut group = c.benchmark_group("cave_paths");
+    for seed in SEEDS {
+        let mut cave = Cave::generate(seed);
+        let (start, end) = cave.endpoints();
+
+        for (name, path_finder) in [
+            ("astar", PathFinder::Astar),
+            ("dijkstras", PathFinder::Dijkstras),
+            ("jump_point", PathFinder::JumpPoint),
+        ] {
+            group.bench_function(BenchmarkId::new(name, seed), |b| {
+                b.iter(|| path_finder.compute(start, end, 0, true, &mut cave, &q_blocks_movement))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, cave_paths);
+criterion_main!(benches);
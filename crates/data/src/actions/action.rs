@@ -0,0 +1,52 @@
+use std::fmt::Debug;
+
+use crate::prelude::*;
+
+pub const TURN_TIME: u32 = 1000;
+pub const WAIT_TIME: u32 = 1000;
+pub const ATTACK_TIME: u32 = 1000;
+
+pub type BoxedAction = Box<dyn Action>;
+
+/// Broad sort of an action, for anything which treats them differently such as
+/// `SpeedModifier`s.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    #[default]
+    Other,
+    Wait,
+    Movement,
+    Attack,
+}
+
+/// What came of performing an action.
+#[derive(Debug)]
+pub enum ActionOutcome {
+    Performed,
+    /// Performed for this turn, perform it again on the actor's next turn.
+    /// Used by actions which take several turns, such as resting.
+    Continue,
+    /// Perform this instead, such as a step turning into a move to the tile it leads to.
+    Alternate(BoxedAction),
+}
+
+/// Something an actor can spend its turn on.
+///
+/// The player's actions are queued in the `ActionQueue`, and the AI picks
+/// `AIComponent::preferred_action`. Either way they are validated and performed when
+/// the actor's turn comes up, taking [`Action::cost`] adjusted by the actor's `Speed`.
+pub trait Action: Debug + Send + Sync + 'static {
+    fn kind(&self) -> ActionKind { ActionKind::Other }
+
+    /// Where the action is aimed, if anywhere.
+    fn target(&self) -> Option<Position> { None }
+
+    /// Check `entity` can perform the action, before anything is changed.
+    fn validate(&self, _entity: Entity, _world: &mut World) -> Result<(), ActionFailure> { Ok(()) }
+
+    /// Time the action takes at `NORMAL_SPEED`.
+    fn cost(&self, entity: Entity, world: &World) -> u32;
+
+    /// Multi-turn actions should check their `Interrupts` before anything else.
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure>;
+}
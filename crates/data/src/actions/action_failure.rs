@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+/// Why an action couldn't be performed, worded to be shown to the player.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ActionFailure {
+    #[error("You can't do that right now.")]
+    MissingComponents,
+    #[error("There's no way to get to {}.", .0)]
+    NoPath(Position),
+    #[error("The way to {} is blocked.", .0)]
+    Blocked(Position),
+    #[error("There's nothing to attack at {}.", .0)]
+    NoTarget(Position),
+    #[error("You're already at full health.")]
+    AtFullHealth,
+    #[error("There's nothing to channel.")]
+    NothingToChannel,
+    #[error("There's nothing left to explore.")]
+    NothingToExplore,
+    #[error("You were interrupted: {}.", .0)]
+    Interrupted(#[from] Interruption),
+    #[error("{}", .0)]
+    Other(String),
+}
@@ -0,0 +1,157 @@
+use crate::prelude::*;
+
+/// What can interrupt a multi-turn action.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InterruptTriggers {
+    /// A hostile the actor hadn't seen yet comes into view.
+    pub hostile_in_view: bool,
+    /// An item the actor hadn't seen yet comes into view.
+    pub item_in_view: bool,
+    /// The actor loses health.
+    pub damage_taken: bool,
+    /// A new message is added to the `MessageLog`.
+    pub message: bool,
+}
+
+impl InterruptTriggers {
+    pub const ALL: Self = Self {
+        hostile_in_view: true,
+        item_in_view: true,
+        damage_taken: true,
+        message: true,
+    };
+    pub const NONE: Self = Self {
+        hostile_in_view: false,
+        item_in_view: false,
+        damage_taken: false,
+        message: false,
+    };
+}
+
+/// Why a multi-turn action was interrupted.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interruption {
+    #[error("a hostile came into view")]
+    HostileInView,
+    #[error("you spotted an item")]
+    ItemInView,
+    #[error("you took damage")]
+    DamageTaken,
+    #[error("something happened")]
+    Message,
+}
+
+/// Watches for the `InterruptTriggers` of a multi-turn action.
+///
+/// Anything which should interrupt the action is compared against what the actor
+/// knew about at its last turn. Only the triggers are saved, so a loaded action
+/// takes note of things again on its first turn.
+#[derive(Reflect, FromReflect, Debug, Default, Clone)]
+pub struct Interrupts {
+    pub triggers: InterruptTriggers,
+    #[reflect(ignore)]
+    is_watching: bool,
+    #[reflect(ignore)]
+    health: Option<i32>,
+    #[reflect(ignore)]
+    noticed: HashSet<Entity>,
+    #[reflect(ignore)]
+    messages: usize,
+}
+
+impl Interrupts {
+    pub fn new(triggers: InterruptTriggers) -> Self {
+        Self {
+            triggers,
+            ..Default::default()
+        }
+    }
+
+    /// Check for anything which happened since the last check.
+    /// The first check only takes note of how things are.
+    pub fn check(&mut self, entity: Entity, world: &mut World) -> Result<(), Interruption> {
+        let health = world.get::<Health>(entity).map(|health| health.current_hp);
+        let messages = world.get_resource::<MessageLog>().map_or(0, |message_log| message_log.total());
+        let hostiles =
+            if self.triggers.hostile_in_view { Self::visible_hostiles(entity, world) } else { Vec::new() };
+        let items = if self.triggers.item_in_view { Self::visible_items(entity, world) } else { Vec::new() };
+
+        let was_watching = std::mem::replace(&mut self.is_watching, true);
+        let lost_health = matches!((self.health, health), (Some(last), Some(current)) if current < last);
+        let new_message = messages > self.messages;
+        let spotted_hostile = hostiles.into_iter().fold(false, |spotted, hostile| {
+            self.noticed.insert(hostile) || spotted
+        });
+        let spotted_item =
+            items.into_iter().fold(false, |spotted, item| self.noticed.insert(item) || spotted);
+        self.health = health;
+        self.messages = messages;
+
+        if !was_watching {
+            Ok(())
+        } else if self.triggers.hostile_in_view && spotted_hostile {
+            Err(Interruption::HostileInView)
+        } else if self.triggers.item_in_view && spotted_item {
+            Err(Interruption::ItemInView)
+        } else if self.triggers.damage_taken && lost_health {
+            Err(Interruption::DamageTaken)
+        } else if self.triggers.message && new_message {
+            Err(Interruption::Message)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn visible_hostiles(entity: Entity, world: &mut World) -> Vec<Entity> {
+        let mut hostile_q = world.query::<(Entity, &Position, &AIComponent)>();
+        let Some(viewshed) = world.get::<Viewshed>(entity) else { return Vec::new(); };
+
+        hostile_q
+            .iter(world)
+            .filter(|(hostile, &position, ai_component)| {
+                *hostile != entity && ai_component.is_hostile() && viewshed.is_visible(position)
+            })
+            .map(|(hostile, ..)| hostile)
+            .collect()
+    }
+
+    fn visible_items(entity: Entity, world: &mut World) -> Vec<Entity> {
+        let mut item_q = world.query_filtered::<(Entity, &Position), With<Equipable>>();
+        let Some(viewshed) = world.get::<Viewshed>(entity) else { return Vec::new(); };
+
+        item_q
+            .iter(world)
+            .filter(|(_, &position)| viewshed.is_visible(position))
+            .map(|(item, _)| item)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_enabled_triggers_interrupt() {
+        let mut world = World::new();
+        world.init_resource::<MessageLog>();
+        let entity = world.spawn(Health::full(10)).id();
+
+        let mut interrupts = Interrupts::new(InterruptTriggers {
+            damage_taken: true,
+            ..InterruptTriggers::NONE
+        });
+        assert_eq!(interrupts.check(entity, &mut world), Ok(()));
+
+        // Messages aren't a trigger here.
+        world.resource_mut::<MessageLog>().add("Something happened.");
+        assert_eq!(interrupts.check(entity, &mut world), Ok(()));
+
+        world.get_mut::<Health>(entity).unwrap().current_hp = 9;
+        assert_eq!(
+            interrupts.check(entity, &mut world),
+            Err(Interruption::DamageTaken)
+        );
+        assert_eq!(interrupts.check(entity, &mut world), Ok(()));
+    }
+}
@@ -13,6 +13,10 @@ pub enum PlayerAction {
     West,
 
     Wait,
+    Rest,
+    Search,
+    AutoExplore,
+    Travel,
 }
 impl PlayerAction {
     // Lists like this can be very useful for quickly matching subsets of actions
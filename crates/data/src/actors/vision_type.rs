@@ -41,6 +41,10 @@ impl TryFrom<VisionType> for u8 {
 
 impl VisionType {
     pub fn as_u8(self) -> u8 { self.try_into().unwrap_or(Self::None as u8) }
+
+    /// Can `vision_type` only see lit tiles?
+    /// `Infared` sees heat, so it doesn't care about light.
+    pub const fn needs_light(vision_type: u8) -> bool { vision_type & Self::Infared as u8 == 0 }
 }
 
 impl_as_primative!(VisionType);
@@ -1,10 +1,14 @@
 use crate::prelude::*;
 
-#[derive(Reflect, Component, Default, Clone, Copy)]
+#[derive(Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct AIComponent {
     ai_type: AIType,
-    pub preferred_action: Option<ActionType>,
+    #[reflect(ignore)]
+    pub preferred_action: Option<BoxedAction>,
+    /// A multi-turn action carrying on, performed instead of the preferred action.
+    #[reflect(ignore)]
+    pub ongoing_action: Option<BoxedAction>,
 }
 
 impl AIComponent {
@@ -13,15 +17,24 @@ impl AIComponent {
         Self {
             ai_type,
             preferred_action: None,
+            ongoing_action: None,
         }
     }
 }
 
+impl AIComponent {
+    pub const fn ai_type(&self) -> AIType { self.ai_type }
+
+    /// Will this actor go after the player on sight?
+    pub const fn is_hostile(&self) -> bool { matches!(self.ai_type, AIType::Aggressive) }
+}
+
 impl AIComponent {
     pub const fn human() -> Self {
         Self {
             ai_type: AIType::Player,
             preferred_action: None,
+            ongoing_action: None,
         }
     }
 
@@ -29,6 +42,7 @@ impl AIComponent {
         Self {
             ai_type: AIType::Scared,
             preferred_action: None,
+            ongoing_action: None,
         }
     }
 
@@ -36,6 +50,7 @@ impl AIComponent {
         Self {
             ai_type: AIType::Aggressive,
             preferred_action: None,
+            ongoing_action: None,
         }
     }
 }
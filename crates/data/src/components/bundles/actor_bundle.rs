@@ -3,13 +3,19 @@ use crate::prelude::*;
 pub struct ActorBundle {
     pub name: Name,
     pub mob: Mob,
+    pub persistent_id: PersistentId,
     pub health: Health,
     pub ai: AIComponent,
     pub position: Position,
 
     pub fov: FieldOfView,
+    pub fov_algorithm: Fov,
+    pub facing: Facing,
+    pub viewshed: Viewshed,
     pub vision_component: Vision,
     pub movement_component: Movement,
+    pub path_cache: PathCache,
+    pub speed: Speed,
 
     pub target_visualizer: TargetVisualizer,
 
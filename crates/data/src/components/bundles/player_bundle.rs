@@ -3,6 +3,7 @@ use crate::prelude::*;
 pub struct PlayerBundle {
     #[bundle]
     pub actor: ActorBundle,
+    pub light: LightSource,
     #[bundle]
     pub input_manager: InputManagerBundle<PlayerAction>,
 }
@@ -59,7 +60,16 @@ impl PlayerBundle {
         input_map
             // Waiting
             .insert(KeyCode::Period, Wait)
-            .insert(KeyCode::Numpad5, Wait);
+            .insert(KeyCode::Numpad5, Wait)
+            // Resting until healed
+            .insert(KeyCode::R, Rest)
+            // Searching for a few turns
+            .insert(KeyCode::F, Search);
+        input_map
+            // Exploring
+            .insert(KeyCode::O, AutoExplore)
+            // Travel to the tile under the cursor
+            .insert(MouseButton::Left, Travel);
         input_map
     }
 }
@@ -0,0 +1,114 @@
+use crate::prelude::*;
+
+/// Which way an entity is looking.
+///
+/// Angles are in degrees counter-clockwise from East, the same as `GridPoint::angle_to`.
+/// Entities only see inside a cone `half_angle` degrees either side of `angle`,
+/// a `half_angle` of `180` sees all the way around.
+#[derive(Reflect, Component, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct Facing {
+    pub angle: f32,
+    pub half_angle: f32,
+}
+
+impl Default for Facing {
+    fn default() -> Self { Self::new(0.0, 180.0) }
+}
+
+impl Facing {
+    pub fn new(angle: f32, half_angle: f32) -> Self {
+        Self {
+            angle: Self::normalize(angle),
+            half_angle: half_angle.clamp(0.0, 180.0),
+        }
+    }
+
+    pub fn from_direction(direction: GridDirection, half_angle: f32) -> Self {
+        Self::new(Self::direction_angle(direction), half_angle)
+    }
+
+    pub fn set_angle(&mut self, angle: f32) { self.angle = Self::normalize(angle); }
+
+    pub fn set_direction(&mut self, direction: GridDirection) {
+        self.set_angle(Self::direction_angle(direction));
+    }
+
+    /// Turn to face `to` when standing at `from`.
+    /// Standing on the target leaves the facing alone.
+    pub fn look_at(&mut self, from: Position, to: Position) {
+        let offset = from.offset_to(to);
+        if offset != IVec2::ZERO {
+            self.set_angle(IVec2::ZERO.angle_to(offset));
+        }
+    }
+
+    /// Is `offset` (relative to the viewer) inside the cone?
+    /// The viewer's own tile always is.
+    pub fn contains(&self, offset: IVec2) -> bool {
+        offset == IVec2::ZERO ||
+            self.half_angle >= 180.0 ||
+            Self::difference(self.angle, IVec2::ZERO.angle_to(offset)) <= self.half_angle
+    }
+
+    /// Does any part of the cone fall within `spread` degrees of `angle`?
+    pub fn overlaps(&self, angle: f32, spread: f32) -> bool {
+        Self::difference(self.angle, angle) <= self.half_angle + spread
+    }
+
+    fn direction_angle(direction: GridDirection) -> f32 { IVec2::ZERO.angle_to(direction.coord()) }
+
+    /// Smallest angle between `a` and `b`, `0..=180`.
+    fn difference(a: f32, b: f32) -> f32 {
+        let difference = (a - b).rem_euclid(360.0);
+        difference.min(360.0 - difference)
+    }
+
+    fn normalize(angle: f32) -> f32 { angle.rem_euclid(360.0) }
+}
+
+impl From<GridDirection> for Facing {
+    fn from(direction: GridDirection) -> Self { Self::from_direction(direction, 180.0) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_only_the_cone() {
+        let north = Facing::new(90.0, 45.0);
+        assert!(north.contains(IVec2::ZERO));
+        assert!(north.contains(IVec2::new(0, 3)));
+        assert!(north.contains(IVec2::new(1, 3)));
+        assert!(!north.contains(IVec2::new(3, 1)));
+        assert!(!north.contains(IVec2::new(0, -3)));
+
+        let all_around = Facing::new(90.0, 180.0);
+        assert!(all_around.contains(IVec2::new(0, -3)));
+    }
+
+    #[test]
+    fn contains_wraps_around_east() {
+        let facing = Facing::new(-10.0, 30.0);
+        assert_eq!(facing.angle, 350.0);
+        assert!(facing.contains(IVec2::new(3, -1)));
+        assert!(facing.contains(IVec2::new(3, 1)));
+        assert!(!facing.contains(IVec2::new(0, 3)));
+        assert!(!facing.contains(IVec2::new(-3, 0)));
+    }
+
+    #[test]
+    fn overlaps_counts_both_spreads() {
+        let north = Facing::new(90.0, 30.0);
+        assert!(north.overlaps(90.0, 45.0));
+        assert!(north.overlaps(45.0, 15.0));
+        assert!(!north.overlaps(0.0, 45.0));
+        assert!(!north.overlaps(270.0, 45.0));
+        assert!(Facing::new(90.0, 50.0).overlaps(0.0, 45.0));
+
+        // Angles either side of East are close together.
+        assert!(Facing::new(350.0, 10.0).overlaps(0.0, 0.0));
+        assert!(Facing::new(350.0, 5.0).overlaps(-30.0, 20.0));
+    }
+}
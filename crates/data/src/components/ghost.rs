@@ -0,0 +1,8 @@
+use crate::prelude::*;
+
+/// A faded copy of an entity, drawn where it was last seen.
+/// Ghosts only exist while their map is the current map, they are despawned on leaving it.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Ghost {
+    pub world_position: WorldPosition,
+}
@@ -0,0 +1,63 @@
+use crate::prelude::*;
+
+/// How quickly a light dims towards the edge of its radius.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LightFalloff {
+    /// Full brightness all the way to the edge.
+    None,
+    #[default]
+    Linear,
+    /// Bright near the source, dropping off quickly.
+    Quadratic,
+}
+
+impl LightFalloff {
+    /// Brightness `0.0..=1.0` at `distance` tiles from a light with `radius`.
+    pub fn intensity(self, distance: f32, radius: f32) -> f32 {
+        if distance > radius {
+            return 0.0;
+        }
+
+        let linear = 1.0 - distance / (radius + 1.0);
+        match self {
+            Self::None => 1.0,
+            Self::Linear => linear,
+            Self::Quadratic => linear * linear,
+        }
+    }
+}
+
+/// Lights up the tiles around an entity, following the same opacity rules as
+/// `VisionType::Normal`.
+#[derive(Reflect, Component, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct LightSource {
+    pub radius: u8,
+    pub color: Color,
+    pub falloff: LightFalloff,
+}
+
+impl Default for LightSource {
+    fn default() -> Self { Self::new(6, Color::rgb(1.0, 0.85, 0.6), LightFalloff::Linear) }
+}
+
+impl LightSource {
+    pub const fn new(radius: u8, color: Color, falloff: LightFalloff) -> Self {
+        Self {
+            radius,
+            color,
+            falloff,
+        }
+    }
+
+    /// Light reaching a tile at `offset` from the source.
+    pub fn light_at(&self, offset: IVec2) -> Color {
+        let distance = DistanceAlg::Pythagoras.distance2d(IVec2::ZERO, offset);
+        let intensity = self.falloff.intensity(distance, self.radius as f32);
+        Color::rgb(
+            self.color.r() * intensity,
+            self.color.g() * intensity,
+            self.color.b() * intensity,
+        )
+    }
+}
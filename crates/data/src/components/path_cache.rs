@@ -0,0 +1,133 @@
+use crate::prelude::*;
+
+/// How far a goal can drift before a cached route to it is thrown away.
+pub const PATH_GOAL_TOLERANCE: u32 = 2;
+
+/// The rest of an actor's route, so `try_move` can take the next step
+/// instead of path finding again every turn.
+///
+/// Invalidated by `invalidate_paths` when a blocker or terrain changes along the route,
+/// and ignored once the goal moves more than `PATH_GOAL_TOLERANCE` tiles away.
+#[derive(Component, Default, Debug, Clone)]
+pub struct PathCache {
+    goal: Option<Position>,
+    /// Remaining steps, in the order of last point -> first point like `PathFinder::compute`.
+    steps: Vec<Position>,
+}
+
+impl PathCache {
+    pub fn new() -> Self { Self::default() }
+
+    pub const fn goal(&self) -> Option<Position> { self.goal }
+
+    pub fn steps(&self) -> &[Position] { &self.steps }
+
+    pub fn is_empty(&self) -> bool { self.steps.is_empty() }
+
+    pub fn set(&mut self, goal: Position, steps: Vec<Position>) {
+        self.goal = Some(goal);
+        self.steps = steps;
+    }
+
+    /// Throw away the route, the next move will path find again.
+    pub fn invalidate(&mut self) {
+        self.goal = None;
+        self.steps.clear();
+    }
+
+    /// Is the route still heading somewhere close enough to `goal`?
+    pub fn is_valid_for(&self, goal: Position) -> bool {
+        !self.steps.is_empty() &&
+            self.goal.map_or(false, |cached| cached.distance(goal) <= PATH_GOAL_TOLERANCE)
+    }
+
+    /// Does the rest of the route pass through `position`?
+    pub fn passes_through(&self, position: Position) -> bool { self.steps.contains(&position) }
+
+    /// Pops the next step from `from` towards `goal`, or `None` if the route
+    /// no longer fits and needs path finding again.
+    pub fn next_step(&mut self, from: Position, goal: Position) -> Option<Position> {
+        if !self.is_valid_for(goal) {
+            return None;
+        }
+
+        let step = self.steps.pop()?;
+        // Something moved us off the route.
+        // Taking the stairs is the only step that isn't to a neighbouring tile.
+        let is_stairs = step.get_world_position().z() != from.get_world_position().z();
+        if !is_stairs && from.distance(step) > 1 {
+            self.invalidate();
+            return None;
+        }
+
+        Some(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(x: u32, y: u32, z: i32) -> Position {
+        Position::new(
+            WorldPosition::new(0, 0, z),
+            LocalPosition::new(x, y, MapLayer::Actors as u32),
+        )
+    }
+
+    /// A cache heading east from (1, 1) to (4, 1).
+    fn east() -> PathCache {
+        let mut path_cache = PathCache::new();
+        path_cache.set(position(4, 1, 0), vec![
+            position(4, 1, 0),
+            position(3, 1, 0),
+            position(2, 1, 0),
+        ]);
+        path_cache
+    }
+
+    #[test]
+    fn follows_a_goal_within_tolerance() {
+        let mut path_cache = east();
+        assert_eq!(
+            path_cache.next_step(position(1, 1, 0), position(4, 1, 0)),
+            Some(position(2, 1, 0))
+        );
+
+        let drifted = position(4, 1 + PATH_GOAL_TOLERANCE, 0);
+        assert_eq!(
+            path_cache.next_step(position(2, 1, 0), drifted),
+            Some(position(3, 1, 0))
+        );
+
+        // Too far, but the route is kept in case the goal comes back.
+        let moved = position(4, 2 + PATH_GOAL_TOLERANCE, 0);
+        assert_eq!(path_cache.next_step(position(3, 1, 0), moved), None);
+        assert_eq!(path_cache.steps(), &[position(4, 1, 0)]);
+    }
+
+    #[test]
+    fn invalidates_when_off_route() {
+        let mut path_cache = east();
+        assert_eq!(
+            path_cache.next_step(position(1, 5, 0), position(4, 1, 0)),
+            None
+        );
+        assert!(path_cache.is_empty());
+        assert_eq!(path_cache.goal(), None);
+    }
+
+    #[test]
+    fn takes_the_stairs() {
+        let mut path_cache = PathCache::new();
+        path_cache.set(position(9, 9, -1), vec![
+            position(9, 9, -1),
+            position(5, 5, -1),
+        ]);
+        // The stairs down at (5, 5) are nowhere near (1, 1) on the map below.
+        assert_eq!(
+            path_cache.next_step(position(1, 1, 0), position(9, 9, -1)),
+            Some(position(5, 5, -1))
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+/// An id which stays the same across saving and loading, unlike `Entity`.
+///
+/// Anything saved which refers to an entity should store this instead,
+/// then find the entity again by it once the world is loaded.
+/// Handed out by `PersistentIds`.
+#[derive(
+    Reflect,
+    Component,
+    Serialize,
+    Deserialize,
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[reflect(Component)]
+pub struct PersistentId(pub u64);
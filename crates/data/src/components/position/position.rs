@@ -48,6 +48,13 @@ impl Position {
             .unsigned_abs()
     }
 
+    /// Offset from this position to `other`, crossing map boundaries.
+    pub fn offset_to(&self, other: Self) -> IVec2 {
+        let (self_x, self_y, _) = self.to_absolute_position();
+        let (other_x, other_y, _) = other.to_absolute_position();
+        IVec2::new((other_x - self_x) as i32, (other_y - self_y) as i32)
+    }
+
     pub fn lerp(&self, other: Self, percent: f32) -> Self {
         let layer = self.layer();
         let (abs_self_x, abs_self_y, abs_self_z) = self.to_absolute_position();
@@ -0,0 +1,192 @@
+use crate::prelude::*;
+
+/// Speed of an actor acting at the normal pace, once every `TURN_TIME`.
+pub const NORMAL_SPEED: u32 = 100;
+
+/// Speed percentage of movement while running.
+pub const RUN_SPEED: u32 = 200;
+
+/// Which actions a `SpeedModifier` changes the speed of.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedTarget {
+    #[default]
+    All,
+    Movement,
+    Attack,
+}
+
+impl SpeedTarget {
+    pub const fn applies_to(&self, kind: ActionKind) -> bool {
+        match self {
+            Self::All => true,
+            Self::Movement => matches!(kind, ActionKind::Movement),
+            Self::Attack => matches!(kind, ActionKind::Attack),
+        }
+    }
+}
+
+/// Speeds up or slows down some of an actor's actions, such as haste, slow,
+/// or heavy armour slowing attacks.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy)]
+pub struct SpeedModifier {
+    pub target: SpeedTarget,
+    /// Percentage of speed, `200` acts twice as fast, `50` half as fast.
+    pub percent: u32,
+    /// Time until the modifier wears off, counted like `TURN_TIME`.
+    /// `None` lasts until it's removed.
+    pub time_left: Option<u32>,
+}
+
+impl SpeedModifier {
+    pub const fn new(target: SpeedTarget, percent: u32) -> Self {
+        Self {
+            target,
+            percent,
+            time_left: None,
+        }
+    }
+
+    /// A modifier lasting `turns` turns of `TURN_TIME`, however many actions fit into them.
+    pub const fn timed(target: SpeedTarget, percent: u32, turns: u32) -> Self {
+        Self {
+            target,
+            percent,
+            time_left: Some(turns.saturating_mul(TURN_TIME)),
+        }
+    }
+
+    pub const fn haste(turns: u32) -> Self { Self::timed(SpeedTarget::All, 200, turns) }
+
+    pub const fn slow(turns: u32) -> Self { Self::timed(SpeedTarget::All, 50, turns) }
+}
+
+/// How quickly an actor acts, used to work out how long each action takes.
+///
+/// Twice the `NORMAL_SPEED` takes half the time, so a fast monster
+/// gets two actions in for every one of the player's.
+#[derive(Reflect, Component, Debug, Clone)]
+#[reflect(Component)]
+pub struct Speed {
+    pub base: u32,
+    pub modifiers: Vec<SpeedModifier>,
+}
+
+impl Default for Speed {
+    fn default() -> Self { Self::new(NORMAL_SPEED) }
+}
+
+impl Speed {
+    pub const fn new(base: u32) -> Self {
+        Self {
+            base,
+            modifiers: Vec::new(),
+        }
+    }
+
+    pub fn add_modifier(&mut self, modifier: SpeedModifier) { self.modifiers.push(modifier); }
+
+    /// Speed percentage for actions of `kind`, with every modifier applied.
+    /// Movement is sped up to `RUN_SPEED` when `movement_type` includes running.
+    pub fn speed_for(&self, kind: ActionKind, movement_type: u8) -> u32 {
+        let mut speed = self.base as u64;
+        for modifier in self.modifiers.iter().filter(|modifier| modifier.target.applies_to(kind)) {
+            speed = speed * modifier.percent as u64 / NORMAL_SPEED as u64;
+        }
+
+        if SpeedTarget::Movement.applies_to(kind) && movement_type & MovementType::Run.as_u8() != 0 {
+            speed = speed * RUN_SPEED as u64 / NORMAL_SPEED as u64;
+        }
+
+        speed.clamp(1, u32::MAX as u64) as u32
+    }
+
+    /// Time an action of `kind` costing `base_time` takes this actor,
+    /// never less than 1 so time always moves on.
+    pub fn time_to_perform(&self, base_time: u32, kind: ActionKind, movement_type: u8) -> u32 {
+        let time = base_time as u64 * NORMAL_SPEED as u64 / self.speed_for(kind, movement_type) as u64;
+        time.clamp(1, u32::MAX as u64) as u32
+    }
+
+    /// Count timed modifiers down by `time_spent`, removing any which wore off.
+    pub fn tick(&mut self, time_spent: u32) {
+        self.modifiers.retain_mut(|modifier| match &mut modifier.time_left {
+            Some(time_left) => {
+                *time_left = time_left.saturating_sub(time_spent);
+                *time_left > 0
+            },
+            None => true,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_change_action_time() {
+        let walk = MovementType::Walk.as_u8();
+        let mut speed = Speed::default();
+        assert_eq!(
+            speed.time_to_perform(TURN_TIME, ActionKind::Movement, walk),
+            TURN_TIME
+        );
+
+        // Running halves move time, but not attacks.
+        let run = walk | MovementType::Run.as_u8();
+        assert_eq!(
+            speed.time_to_perform(TURN_TIME, ActionKind::Movement, run),
+            TURN_TIME / 2
+        );
+        assert_eq!(
+            speed.time_to_perform(ATTACK_TIME, ActionKind::Attack, run),
+            ATTACK_TIME
+        );
+
+        // Heavy armour slows attacks only.
+        speed.add_modifier(SpeedModifier::new(SpeedTarget::Attack, 50));
+        assert_eq!(
+            speed.time_to_perform(ATTACK_TIME, ActionKind::Attack, walk),
+            ATTACK_TIME * 2
+        );
+        assert_eq!(
+            speed.time_to_perform(WAIT_TIME, ActionKind::Wait, walk),
+            WAIT_TIME
+        );
+
+        // Haste speeds everything up until it wears off.
+        speed.add_modifier(SpeedModifier::haste(2));
+        assert_eq!(
+            speed.time_to_perform(WAIT_TIME, ActionKind::Wait, walk),
+            WAIT_TIME / 2
+        );
+        speed.tick(TURN_TIME);
+        assert_eq!(
+            speed.time_to_perform(WAIT_TIME, ActionKind::Wait, walk),
+            WAIT_TIME / 2
+        );
+        speed.tick(TURN_TIME);
+        assert_eq!(
+            speed.time_to_perform(WAIT_TIME, ActionKind::Wait, walk),
+            WAIT_TIME
+        );
+        assert_eq!(speed.modifiers.len(), 1);
+    }
+
+    #[test]
+    fn timed_modifiers_last_turns_not_actions() {
+        let mut speed = Speed::default();
+        speed.add_modifier(SpeedModifier::haste(10));
+
+        // Hasted actions take half a turn, so 10 turns fit 20 of them.
+        for _ in 0..19 {
+            let time_spent = speed.time_to_perform(TURN_TIME, ActionKind::Movement, 0);
+            assert_eq!(time_spent, TURN_TIME / 2);
+            speed.tick(time_spent);
+        }
+        assert_eq!(speed.modifiers.len(), 1);
+
+        speed.tick(TURN_TIME / 2);
+        assert!(speed.modifiers.is_empty());
+    }
+}
@@ -65,27 +65,57 @@ impl TargetVisualizer {
         let line = grid_shapes::Line::new(start, end);
         for position: Position in line.iter() {
             if position.get_world_position() == map_manager.get_current_world_position() {
-                self.entity_list.push((
-                    position,
-                    commands
-                        .spawn(SpriteSheetBundle {
-                            sprite: TextureAtlasSprite {
-                                color: self.color,
-                                index: usize::from(self.style),
-                                custom_size: Some(Vec2::ONE),
-                                ..Default::default()
-                            },
-                            texture_atlas: tileset.atlas().clone(),
-                            transform: Transform::from_translation(position.translation()),
-                            ..default()
-                        })
-                        .id(),
-                ));
+                self.spawn_marker(commands, tileset, position);
             }
 
         }
     }
 
+    /// Draws every tile of `path` instead of a straight line, such as a path
+    /// returned by `PathFinder::compute` in the order of last point -> first point.
+    pub fn update_path(
+        &mut self,
+        commands: &mut Commands,
+        map_manager: &MapManager,
+        tilesets: &Tilesets,
+        start: Position,
+        path: &[Position],
+    ) {
+        let Some(tileset) = tilesets.get_by_id(&TILESET_UI_ID) else {
+            error!("Couldn't find tilemap_id: {:?}. Refusing to draw TargetVisualizer.", TILESET_UI_ID);
+            return;
+        };
+
+        self.clear(commands);
+        self.start = Some(start);
+        self.end = path.first().copied();
+        for &position in path {
+            if position.get_world_position() == map_manager.get_current_world_position() {
+                self.spawn_marker(commands, tileset, position);
+            }
+        }
+    }
+
+    fn spawn_marker(&mut self, commands: &mut Commands, tileset: &Tileset, mut position: Position) {
+        position.set_layer(MapLayer::UI as u32);
+        self.entity_list.push((
+            position,
+            commands
+                .spawn(SpriteSheetBundle {
+                    sprite: TextureAtlasSprite {
+                        color: self.color,
+                        index: usize::from(self.style),
+                        custom_size: Some(Vec2::ONE),
+                        ..Default::default()
+                    },
+                    texture_atlas: tileset.atlas().clone(),
+                    transform: Transform::from_translation(position.translation()),
+                    ..default()
+                })
+                .id(),
+        ));
+    }
+
     pub fn clear(&mut self, commands: &mut Commands) {
         self.start = None;
         self.end = None;
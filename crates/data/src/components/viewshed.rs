@@ -0,0 +1,43 @@
+use crate::prelude::*;
+
+/// Cached field of view for an entity.
+///
+/// Kept up to date by `update_viewsheds`, which only recomputes it when the entity
+/// moves or turns, or something that blocks vision changes within range.
+/// Read this instead of running the `Fov` again.
+#[derive(Component, Default, Debug, Clone)]
+pub struct Viewshed {
+    visibility_map: VisibilityMap,
+    origin: Option<Position>,
+    facing: Option<Facing>,
+    dirty: bool,
+}
+
+impl Viewshed {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn is_visible(&self, position: Position) -> bool { self.visibility_map.get_visible(position) }
+
+    pub const fn visibility_map(&self) -> &VisibilityMap { &self.visibility_map }
+
+    /// Force a recompute next time viewsheds are updated.
+    pub fn mark_dirty(&mut self) { self.dirty = true; }
+
+    /// Does the cached map still match where the entity is and which way it's looking?
+    pub fn needs_update(&self, origin: Position, facing: Facing) -> bool {
+        self.dirty || self.origin != Some(origin) || self.facing != Some(facing)
+    }
+
+    /// Could a change at `position` affect what this viewshed sees?
+    /// Viewsheds which have never been computed are always affected.
+    pub fn is_affected_by(&self, position: Position, range: u32) -> bool {
+        self.origin.map_or(true, |origin| origin.distance(position) <= range)
+    }
+
+    pub fn set(&mut self, origin: Position, facing: Facing, visibility_map: VisibilityMap) {
+        self.visibility_map = visibility_map;
+        self.origin = Some(origin);
+        self.facing = Some(facing);
+        self.dirty = false;
+    }
+}
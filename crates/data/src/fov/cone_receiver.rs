@@ -0,0 +1,57 @@
+use crate::prelude::*;
+
+/// Passes through only the positions inside a `Facing` cone,
+/// so any `FovAlgorithm` can be limited to a cone.
+pub struct ConeReceiver<'a, R: FovReceiver> {
+    origin: Position,
+    facing: Facing,
+    receiver: &'a mut R,
+}
+
+impl<'a, R: FovReceiver> ConeReceiver<'a, R> {
+    pub fn new(origin: Position, facing: Facing, receiver: &'a mut R) -> Self {
+        Self {
+            origin,
+            facing,
+            receiver,
+        }
+    }
+}
+
+impl<'a, R: FovReceiver> FovReceiver for ConeReceiver<'a, R> {
+    fn set_visible(&mut self, position: Position) {
+        if self.facing.contains(self.origin.offset_to(position)) {
+            self.receiver.set_visible(position);
+        }
+    }
+
+    fn get_visible(&self, position: Position) -> bool { self.receiver.get_visible(position) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_passes_on_the_cone() {
+        let origin = test_position(8, 8);
+        let mut visibility_map = VisibilityMap::new(UVec2::new(16, 16));
+        let mut cone = ConeReceiver::new(origin, Facing::new(0.0, 45.0), &mut visibility_map);
+
+        for position in [
+            origin,
+            test_position(12, 8),
+            test_position(12, 11),
+            test_position(4, 8),
+        ] {
+            cone.set_visible(position);
+        }
+        assert!(cone.get_visible(test_position(12, 8)));
+        assert!(!cone.get_visible(test_position(4, 8)));
+
+        for position in [origin, test_position(12, 8), test_position(12, 11)] {
+            assert!(visibility_map.get_visible(position));
+        }
+        assert!(!visibility_map.get_visible(test_position(4, 8)));
+    }
+}
@@ -0,0 +1,108 @@
+use crate::prelude::*;
+
+/// Half the width of an opaque tile's diamond.
+const DIAMOND_RADIUS: f32 = 0.5;
+
+/// Treats every opaque tile as a diamond inscribed in its square instead of the full square.
+/// A tile is visible if the line between the centers of the origin and the tile doesn't touch
+/// any diamond in between. Pillars cast thin shadows and you can peek between diagonal walls,
+/// while walls themselves are lit evenly.
+pub struct DiamondWalls;
+
+impl<'w, 's> FovAlgorithm<'w, 's> for DiamondWalls {
+    fn compute_fov(
+        origin: Position,
+        vision_type: u8,
+        range: u32,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+        receiver: &mut impl FovReceiver,
+    ) {
+        receiver.set_visible(origin);
+
+        // Every tile is tested against many lines, only ask the provider once.
+        let mut opaque = HashMap::new();
+        let mut is_opaque = |tile: IVec2| {
+            *opaque
+                .entry(tile)
+                .or_insert_with(|| provider.is_opaque(origin + tile, vision_type, q_blocks_vision))
+        };
+
+        let range = range as i32;
+        for tile in RectIter::new(IVec2::splat(-range), IVec2::splat(range)) {
+            // compare the squares, it's faster!
+            if tile == IVec2::ZERO ||
+                DistanceAlg::PythagorasSquared.distance2d(IVec2::ZERO, tile) > range.pow(2) as f32
+            {
+                continue;
+            }
+
+            let blocked = RectIter::new(IVec2::ZERO.min(tile), IVec2::ZERO.max(tile)).any(|between| {
+                between != IVec2::ZERO &&
+                    between != tile &&
+                    Self::line_touches_diamond(tile.as_vec2(), between.as_vec2()) &&
+                    is_opaque(between)
+            });
+
+            if !blocked {
+                receiver.set_visible(origin + tile);
+            }
+        }
+    }
+}
+
+impl DiamondWalls {
+    /// Does the segment from the origin to `end` touch the diamond centered on `center`?
+    ///
+    /// The manhattan distance from `center` along the segment is piecewise linear,
+    /// so its minimum is at an end point or where the segment crosses the
+    /// diamond's axes.
+    fn line_touches_diamond(end: Vec2, center: Vec2) -> bool {
+        let manhattan = |t: f32| {
+            let point = end * t;
+            (point.x - center.x).abs() + (point.y - center.y).abs()
+        };
+
+        let mut closest = manhattan(0.0).min(manhattan(1.0));
+        if end.x != 0.0 {
+            closest = closest.min(manhattan((center.x / end.x).clamp(0.0, 1.0)));
+        }
+        if end.y != 0.0 {
+            closest = closest.min(manhattan((center.y / end.y).clamp(0.0, 1.0)));
+        }
+
+        closest <= DIAMOND_RADIUS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_symmetric() { assert_symmetric(Fov::DiamondWalls); }
+
+    #[test]
+    fn pillars_cast_thin_shadows() {
+        let origin = test_position(8, 8);
+        let pillar = test_position(10, 8);
+        let visibility_map = test_fov(Fov::DiamondWalls, origin, 6, &[pillar]);
+
+        assert!(visibility_map.get_visible(pillar));
+        assert!(!visibility_map.get_visible(test_position(12, 8)));
+        assert!(!visibility_map.get_visible(test_position(13, 9)));
+        // Just off the line through the pillar's center.
+        assert!(visibility_map.get_visible(test_position(12, 10)));
+        assert!(visibility_map.get_visible(test_position(12, 6)));
+    }
+
+    #[test]
+    fn peeks_between_diagonal_walls() {
+        let origin = test_position(8, 8);
+        let walls = [test_position(9, 8), test_position(8, 9)];
+        let visibility_map = test_fov(Fov::DiamondWalls, origin, 6, &walls);
+
+        assert!(visibility_map.get_visible(test_position(9, 9)));
+        assert!(visibility_map.get_visible(test_position(11, 11)));
+    }
+}
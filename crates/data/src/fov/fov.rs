@@ -1,7 +1,21 @@
 use crate::prelude::*;
+
+/// Which algorithm an entity uses to see the world.
+/// Trade accuracy against speed per entity.
+#[derive(Reflect, FromReflect, Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
 pub enum Fov {
+    /// Symmetric shadowcasting, the default.
+    #[default]
     Shadowcast,
+    /// Shadowcasting limited to a single quadrant.
     ShadowcastDirection(CardinalDirection),
+    /// Sees anything visible from any part of its tile, the most generous.
+    PrecisePermissive,
+    /// Cheap rays to the edge of the range, can leave gaps.
+    Raycast,
+    /// Opaque tiles are treated as diamonds, pillars cast thin shadows.
+    DiamondWalls,
 }
 impl Fov {
     pub fn compute<'w, 's, FovRange: Into<u32>>(
@@ -32,6 +46,142 @@ impl Fov {
                 receiver,
                 *direction,
             ),
+            Self::PrecisePermissive => PrecisePermissive::compute_fov(
+                origin,
+                vision_type,
+                range,
+                provider,
+                q_blocks_vision,
+                receiver,
+            ),
+            Self::Raycast => Raycast::compute_fov(
+                origin,
+                vision_type,
+                range,
+                provider,
+                q_blocks_vision,
+                receiver,
+            ),
+            Self::DiamondWalls => DiamondWalls::compute_fov(
+                origin,
+                vision_type,
+                range,
+                provider,
+                q_blocks_vision,
+                receiver,
+            ),
+        }
+    }
+
+    /// Same as `compute`, but only sees inside the `facing` cone.
+    pub fn compute_cone<'w, 's, FovRange: Into<u32>>(
+        &self,
+        facing: Facing,
+        origin: Position,
+        vision_type: u8,
+        range: FovRange,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+        receiver: &mut impl FovReceiver,
+    ) {
+        let range = range.into();
+        let mut cone = ConeReceiver::new(origin, facing, receiver);
+        match self {
+            // Only scan the quadrants the cone reaches into.
+            Self::Shadowcast => CardinalDirection::all()
+                .filter(|direction| facing.overlaps(i32::from(*direction) as f32, 45.0))
+                .for_each(|direction| {
+                    Shadowcast::compute_direction(
+                        origin,
+                        vision_type,
+                        range,
+                        provider,
+                        q_blocks_vision,
+                        &mut cone,
+                        direction,
+                    )
+                }),
+            _ => self.compute(
+                origin,
+                vision_type,
+                range,
+                provider,
+                q_blocks_vision,
+                &mut cone,
+            ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compute_cone(fov: Fov, facing: Facing, origin: Position, walls: &[Position]) -> VisibilityMap {
+        let mut world = World::new();
+        let mut system_state: SystemState<Query<&BlocksVision>> = SystemState::new(&mut world);
+        let q_blocks_vision = system_state.get(&world);
+
+        let mut provider = TestWalls(walls.iter().copied().collect());
+        let mut visibility_map = VisibilityMap::new(UVec2::new(32, 32));
+        fov.compute_cone(
+            facing,
+            origin,
+            VisionType::Normal.as_u8(),
+            6u32,
+            &mut provider,
+            &q_blocks_vision,
+            &mut visibility_map,
+        );
+        visibility_map
+    }
+
+    #[test]
+    fn cones_see_what_the_full_view_sees_inside_them() {
+        let origin = test_position(16, 16);
+        let walls = [
+            test_position(17, 18),
+            test_position(14, 15),
+            test_position(19, 16),
+            test_position(16, 13),
+        ];
+
+        for fov in [Fov::Shadowcast, Fov::PrecisePermissive, Fov::DiamondWalls] {
+            let full = test_fov(fov, origin, 6, &walls);
+            for facing in [
+                Facing::new(90.0, 30.0),
+                Facing::new(200.0, 60.0),
+                Facing::new(315.0, 10.0),
+                Facing::new(0.0, 180.0),
+            ] {
+                let cone = compute_cone(fov, facing, origin, &walls);
+                for y in 9..=23 {
+                    for x in 9..=23 {
+                        let position = test_position(x, y);
+                        let expected =
+                            full.get_visible(position) && facing.contains(origin.offset_to(position));
+                        assert_eq!(
+                            cone.get_visible(position),
+                            expected,
+                            "{:?} facing {:?} at {}",
+                            fov,
+                            facing,
+                            position
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cones_only_see_ahead() {
+        let origin = test_position(16, 16);
+        let cone = compute_cone(Fov::Shadowcast, Facing::new(90.0, 45.0), origin, &[]);
+        assert!(cone.get_visible(origin));
+        assert!(cone.get_visible(test_position(16, 21)));
+        assert!(cone.get_visible(test_position(18, 20)));
+        assert!(!cone.get_visible(test_position(21, 16)));
+        assert!(!cone.get_visible(test_position(16, 11)));
+    }
+}
@@ -0,0 +1,386 @@
+use crate::prelude::*;
+
+/// A `FovProvider` which also knows what a projectile would hit.
+pub trait ProjectileProvider: FovProvider {
+    /// The entity a projectile passing through `position` would hit, if any.
+    /// Only entities whose `BlocksMovement` blocks `movement_type` are in the way.
+    fn get_blocking_entity(
+        &mut self,
+        position: Position,
+        movement_type: u8,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Option<Entity>;
+}
+
+/// What stopped a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineHit {
+    /// A tile which blocks vision.
+    Opaque(Position),
+    /// An entity standing in the way.
+    Entity(Entity, Position),
+}
+
+impl LineHit {
+    pub const fn position(&self) -> Position {
+        match self {
+            Self::Opaque(position) | Self::Entity(_, position) => *position,
+        }
+    }
+}
+
+/// The result of walking a line.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineTrace {
+    /// Every `Position` the line passed through, not including the start.
+    /// If something was hit, it's the last `Position`.
+    pub path: Vec<Position>,
+    /// What stopped the line, `None` if it went the whole way.
+    pub hit: Option<LineHit>,
+}
+
+impl LineTrace {
+    /// Did the line make it to `position`?
+    pub fn reached(&self, position: Position) -> bool { self.path.last() == Some(&position) }
+}
+
+/// Line of sight and projectile paths, without computing a whole field of view.
+///
+/// Lines are Bresenham lines in `Position` space, so they cross map edges.
+/// The `_symmetric` variants give the same result no matter which end they start from.
+pub struct LineOfSight;
+
+impl<'w, 's> LineOfSight {
+    /// Every `Position` from `start` to `end` inclusive.
+    pub fn line(start: Position, end: Position) -> impl Iterator<Item = Position> {
+        BresenhamLineInclusiveIter::new(IVec2::ZERO, start.offset_to(end)).map(move |offset| start + offset)
+    }
+
+    /// Same as `line()`, but `line(a, b)` is always `line(b, a)` reversed.
+    pub fn symmetric_line(start: Position, end: Position) -> Vec<Position> {
+        if Self::sort_key(start) <= Self::sort_key(end) {
+            Self::line(start, end).collect()
+        } else {
+            let mut line: Vec<Position> = Self::line(end, start).collect();
+            line.reverse();
+            line
+        }
+    }
+
+    /// Can something at `start` see `end`?
+    /// Only the tiles between the two are checked, so walls can be seen.
+    pub fn can_see(
+        start: Position,
+        end: Position,
+        vision_type: u8,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+    ) -> bool {
+        Self::is_clear(
+            Self::line(start, end),
+            end,
+            vision_type,
+            provider,
+            q_blocks_vision,
+        )
+    }
+
+    /// Same as `can_see()`, but `a` can see `b` exactly when `b` can see `a`.
+    pub fn can_see_symmetric(
+        start: Position,
+        end: Position,
+        vision_type: u8,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+    ) -> bool {
+        Self::is_clear(
+            Self::symmetric_line(start, end),
+            end,
+            vision_type,
+            provider,
+            q_blocks_vision,
+        )
+    }
+
+    /// Walks from `start` to `end`, stopping at the first tile which blocks vision.
+    pub fn trace(
+        start: Position,
+        end: Position,
+        vision_type: u8,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+    ) -> LineTrace {
+        Self::walk(Self::line(start, end).skip(1), |position| {
+            provider.is_opaque(position, vision_type, q_blocks_vision).then_some(LineHit::Opaque(position))
+        })
+    }
+
+    /// Same as `trace()`, but the path from `a` to `b` is the path from `b` to `a` reversed.
+    pub fn trace_symmetric(
+        start: Position,
+        end: Position,
+        vision_type: u8,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+    ) -> LineTrace {
+        Self::walk(
+            Self::symmetric_line(start, end).into_iter().skip(1),
+            |position| {
+                provider
+                    .is_opaque(position, vision_type, q_blocks_vision)
+                    .then_some(LineHit::Opaque(position))
+            },
+        )
+    }
+
+    /// Fires a projectile from `start` toward `target`.
+    ///
+    /// The projectile keeps going past `target` until it has travelled `range` tiles,
+    /// stopping early at the first opaque tile, or entity blocking `movement_type`.
+    pub fn trace_projectile(
+        start: Position,
+        target: Position,
+        range: u32,
+        vision_type: u8,
+        movement_type: u8,
+        provider: &mut impl ProjectileProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+        q_blocks_movement: &Query<'w, 's, &'static BlocksMovement>,
+    ) -> LineTrace {
+        let offset = start.offset_to(target);
+        if offset == IVec2::ZERO {
+            return LineTrace::default();
+        }
+
+        // Stretch the line out so it's at least `range` long, then only take `range` steps.
+        let length = offset.abs().max_element();
+        let scale = (range as i32 + length - 1) / length;
+        let end = start + offset * scale.max(1);
+
+        Self::walk(
+            Self::line(start, end).skip(1).take(range as usize),
+            |position| {
+                let entity = provider.get_blocking_entity(position, movement_type, q_blocks_movement);
+                if let Some(entity) = entity {
+                    return Some(LineHit::Entity(entity, position));
+                }
+                provider
+                    .is_opaque(position, vision_type, q_blocks_vision)
+                    .then_some(LineHit::Opaque(position))
+            },
+        )
+    }
+
+    fn is_clear(
+        line: impl IntoIterator<Item = Position>,
+        end: Position,
+        vision_type: u8,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+    ) -> bool {
+        line.into_iter()
+            .skip(1)
+            .take_while(|&position| position != end)
+            .all(|position| !provider.is_opaque(position, vision_type, q_blocks_vision))
+    }
+
+    fn walk(
+        line: impl Iterator<Item = Position>,
+        mut is_hit: impl FnMut(Position) -> Option<LineHit>,
+    ) -> LineTrace {
+        let mut trace = LineTrace::default();
+        for position in line {
+            trace.path.push(position);
+            if let Some(hit) = is_hit(position) {
+                trace.hit = Some(hit);
+                break;
+            }
+        }
+        trace
+    }
+
+    fn sort_key(position: Position) -> ([i32; 3], u32, u32) {
+        (position.world_xyz().to_array(), position.x(), position.y())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `TestWalls`, with `entities` standing in the way of projectiles.
+    struct Range {
+        walls: TestWalls,
+        entities: HashMap<Position, Entity>,
+    }
+
+    impl Range {
+        fn new(walls: &[Position], entities: &[(Position, Entity)]) -> Self {
+            Self {
+                walls: TestWalls(walls.iter().copied().collect()),
+                entities: entities.iter().copied().collect(),
+            }
+        }
+    }
+
+    impl FovProvider for Range {
+        fn is_opaque(
+            &mut self,
+            position: Position,
+            vision_type: u8,
+            q_blocks_vision: &Query<&BlocksVision>,
+        ) -> bool {
+            self.walls.is_opaque(position, vision_type, q_blocks_vision)
+        }
+    }
+
+    impl ProjectileProvider for Range {
+        fn get_blocking_entity(
+            &mut self,
+            position: Position,
+            _movement_type: u8,
+            _q_blocks_movement: &Query<&BlocksMovement>,
+        ) -> Option<Entity> {
+            self.entities.get(&position).copied()
+        }
+    }
+
+    fn positions(xs: impl IntoIterator<Item = u32>, y: u32) -> Vec<Position> {
+        xs.into_iter().map(|x| test_position(x, y)).collect()
+    }
+
+    /// Fires a projectile along row 2 from `(2, 2)` at `target`, with `range` tiles to go.
+    fn fire(provider: &mut Range, target: Position, range: u32) -> LineTrace {
+        let mut world = World::new();
+        let mut system_state: SystemState<(Query<&BlocksVision>, Query<&BlocksMovement>)> =
+            SystemState::new(&mut world);
+        let (q_blocks_vision, q_blocks_movement) = system_state.get(&world);
+
+        LineOfSight::trace_projectile(
+            test_position(2, 2),
+            target,
+            range,
+            VisionType::Normal.as_u8(),
+            MovementType::Walk as u8,
+            provider,
+            &q_blocks_vision,
+            &q_blocks_movement,
+        )
+    }
+
+    #[test]
+    fn symmetric_lines_match_both_ways() {
+        let a = test_position(2, 3);
+        for end in [
+            test_position(9, 4),
+            test_position(7, 12),
+            test_position(0, 0),
+            test_position(13, 1),
+        ] {
+            let mut reversed = LineOfSight::symmetric_line(end, a);
+            reversed.reverse();
+            assert_eq!(LineOfSight::symmetric_line(a, end), reversed);
+        }
+    }
+
+    #[test]
+    fn line_includes_both_ends() {
+        let line: Vec<Position> = LineOfSight::line(test_position(1, 1), test_position(5, 3)).collect();
+        assert_eq!(line.first(), Some(&test_position(1, 1)));
+        assert_eq!(line.last(), Some(&test_position(5, 3)));
+        assert_eq!(line.len(), 5);
+    }
+
+    #[test]
+    fn walls_are_seen_but_not_seen_through() {
+        let mut world = World::new();
+        let mut system_state: SystemState<Query<&BlocksVision>> = SystemState::new(&mut world);
+        let q_blocks_vision = system_state.get(&world);
+
+        let mut provider = TestWalls([test_position(5, 2)].into_iter().collect());
+        let (start, vision_type) = (test_position(2, 2), VisionType::Normal.as_u8());
+        for (x, visible) in [(4, true), (5, true), (8, false)] {
+            let end = test_position(x, 2);
+            let seen = LineOfSight::can_see(start, end, vision_type, &mut provider, &q_blocks_vision);
+            assert_eq!(seen, visible, "{}", end);
+            let seen =
+                LineOfSight::can_see_symmetric(start, end, vision_type, &mut provider, &q_blocks_vision);
+            assert_eq!(seen, visible, "{}", end);
+        }
+    }
+
+    #[test]
+    fn traces_stop_at_the_first_wall() {
+        let mut world = World::new();
+        let mut system_state: SystemState<Query<&BlocksVision>> = SystemState::new(&mut world);
+        let q_blocks_vision = system_state.get(&world);
+
+        let mut provider = TestWalls(positions([5, 6], 2).into_iter().collect());
+        let vision_type = VisionType::Normal.as_u8();
+
+        let forward = LineOfSight::trace(
+            test_position(2, 2),
+            test_position(9, 2),
+            vision_type,
+            &mut provider,
+            &q_blocks_vision,
+        );
+        assert_eq!(forward.path, positions(3..=5, 2));
+        assert_eq!(forward.hit, Some(LineHit::Opaque(test_position(5, 2))));
+
+        let backward = LineOfSight::trace_symmetric(
+            test_position(9, 2),
+            test_position(2, 2),
+            vision_type,
+            &mut provider,
+            &q_blocks_vision,
+        );
+        assert_eq!(backward.path, positions((6..=8).rev(), 2));
+        assert_eq!(backward.hit, Some(LineHit::Opaque(test_position(6, 2))));
+
+        let clear = LineOfSight::trace(
+            test_position(2, 2),
+            test_position(4, 2),
+            vision_type,
+            &mut provider,
+            &q_blocks_vision,
+        );
+        assert!(clear.reached(test_position(4, 2)));
+        assert_eq!(clear.hit, None);
+    }
+
+    #[test]
+    fn projectiles_stop_at_the_first_entity() {
+        let entity = Entity::from_raw(7);
+        let mut provider = Range::new(&positions([6], 2), &[(test_position(4, 2), entity)]);
+
+        let trace = fire(&mut provider, test_position(9, 2), 10);
+        assert_eq!(trace.path, positions(3..=4, 2));
+        assert_eq!(
+            trace.hit,
+            Some(LineHit::Entity(entity, test_position(4, 2)))
+        );
+    }
+
+    #[test]
+    fn projectiles_stop_at_the_first_wall() {
+        let mut provider = Range::new(&positions([6, 7], 2), &[]);
+
+        let trace = fire(&mut provider, test_position(9, 2), 10);
+        assert_eq!(trace.path, positions(3..=6, 2));
+        assert_eq!(trace.hit, Some(LineHit::Opaque(test_position(6, 2))));
+    }
+
+    #[test]
+    fn projectiles_fly_past_the_target() {
+        let mut provider = Range::new(&positions([8], 2), &[]);
+
+        let trace = fire(&mut provider, test_position(4, 2), 10);
+        assert_eq!(trace.path, positions(3..=8, 2));
+        assert_eq!(trace.hit, Some(LineHit::Opaque(test_position(8, 2))));
+
+        let short = fire(&mut provider, test_position(4, 2), 3);
+        assert_eq!(short.path, positions(3..=5, 2));
+        assert_eq!(short.hit, None);
+    }
+}
@@ -0,0 +1,168 @@
+// FoV implementation adapted from Jonathon Duerig's precise permissive FOV:
+// http://www.roguebasin.com/index.php/Precise_Permissive_Field_of_View
+use super::{sight_line::*, view::*};
+use crate::prelude::*;
+
+/// A tile is visible if any unobstructed line connects some point of the origin's
+/// square to some point of the tile's square. Symmetric, and very generous around corners.
+pub struct PrecisePermissive;
+
+impl<'w, 's> FovAlgorithm<'w, 's> for PrecisePermissive {
+    fn compute_fov(
+        origin: Position,
+        vision_type: u8,
+        range: u32,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+        receiver: &mut impl FovReceiver,
+    ) {
+        receiver.set_visible(origin);
+        for quadrant in [
+            IVec2::new(1, 1),
+            IVec2::new(-1, 1),
+            IVec2::new(1, -1),
+            IVec2::new(-1, -1),
+        ] {
+            Self::check_quadrant(
+                origin,
+                vision_type,
+                range as i32,
+                quadrant,
+                provider,
+                q_blocks_vision,
+                receiver,
+            );
+        }
+    }
+}
+
+impl<'w, 's> PrecisePermissive {
+    fn check_quadrant(
+        origin: Position,
+        vision_type: u8,
+        range: i32,
+        quadrant: IVec2,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+        receiver: &mut impl FovReceiver,
+    ) {
+        let mut bumps = Vec::new();
+        let mut views = vec![View::new(
+            SightLine::new(IVec2::new(0, 1), IVec2::new(range, 0)),
+            SightLine::new(IVec2::new(1, 0), IVec2::new(0, range)),
+        )];
+
+        // Walk the quadrant in diagonal strips moving away from the origin.
+        let mut i = 1;
+        while i <= range * 2 && !views.is_empty() {
+            let mut view_index = 0;
+            let mut j = (i - range).max(0);
+            while j <= i.min(range) && view_index < views.len() {
+                let tile = IVec2::new(i - j, j);
+                let position = origin + tile * quadrant;
+
+                // Find the view this tile falls into.
+                let top_left = IVec2::new(tile.x, tile.y + 1);
+                let bottom_right = IVec2::new(tile.x + 1, tile.y);
+                while view_index < views.len() &&
+                    views[view_index].steep_line.is_below_or_collinear(bottom_right)
+                {
+                    view_index += 1;
+                }
+                if view_index == views.len() || views[view_index].shallow_line.is_above_or_collinear(top_left)
+                {
+                    j += 1;
+                    continue;
+                }
+
+                // compare the squares, it's faster!
+                if DistanceAlg::PythagorasSquared.distance2d(IVec2::ZERO, tile) <= range.pow(2) as f32 {
+                    receiver.set_visible(position);
+                }
+
+                if provider.is_opaque(position, vision_type, q_blocks_vision) {
+                    Self::block_view(
+                        &mut views,
+                        &mut view_index,
+                        &mut bumps,
+                        top_left,
+                        bottom_right,
+                    );
+                }
+
+                j += 1;
+            }
+            i += 1;
+        }
+    }
+
+    /// Narrows, splits or removes the view around an opaque tile.
+    fn block_view(
+        views: &mut Vec<View>,
+        view_index: &mut usize,
+        bumps: &mut Vec<ViewBump>,
+        top_left: IVec2,
+        bottom_right: IVec2,
+    ) {
+        let view = views[*view_index];
+        let shallow_above = view.shallow_line.is_above(bottom_right);
+        let steep_below = view.steep_line.is_below(top_left);
+
+        if shallow_above && steep_below {
+            // The tile fills the whole view.
+            views.remove(*view_index);
+        } else if shallow_above {
+            // The tile only clips the top of the view.
+            views[*view_index].add_shallow_bump(top_left, bumps);
+            if !views[*view_index].is_valid() {
+                views.remove(*view_index);
+            }
+        } else if steep_below {
+            // The tile only clips the bottom of the view.
+            views[*view_index].add_steep_bump(bottom_right, bumps);
+            if !views[*view_index].is_valid() {
+                views.remove(*view_index);
+            }
+        } else {
+            // The tile sits in the middle, split the view in two around it.
+            let shallow_index = *view_index;
+            let mut steep_index = shallow_index + 1;
+            *view_index += 1;
+            views.insert(shallow_index, view);
+
+            views[shallow_index].add_steep_bump(bottom_right, bumps);
+            if !views[shallow_index].is_valid() {
+                views.remove(shallow_index);
+                *view_index -= 1;
+                steep_index -= 1;
+            }
+
+            views[steep_index].add_shallow_bump(top_left, bumps);
+            if !views[steep_index].is_valid() {
+                views.remove(steep_index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_symmetric() { assert_symmetric(Fov::PrecisePermissive); }
+
+    #[test]
+    fn pillars_hide_what_is_behind_them() {
+        let origin = test_position(8, 8);
+        let pillar = test_position(10, 8);
+        let visibility_map = test_fov(Fov::PrecisePermissive, origin, 6, &[pillar]);
+
+        assert!(visibility_map.get_visible(pillar));
+        assert!(!visibility_map.get_visible(test_position(12, 8)));
+        assert!(!visibility_map.get_visible(test_position(14, 8)));
+        // Seen past either side of the pillar.
+        assert!(visibility_map.get_visible(test_position(12, 9)));
+        assert!(visibility_map.get_visible(test_position(12, 7)));
+    }
+}
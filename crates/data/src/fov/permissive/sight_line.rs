@@ -0,0 +1,35 @@
+use crate::prelude::*;
+
+/// A line between two tile corners, used to bound a `View`.
+#[derive(Clone, Copy)]
+pub struct SightLine {
+    pub initial: IVec2,
+    pub terminal: IVec2,
+}
+
+impl SightLine {
+    pub const fn new(initial: IVec2, terminal: IVec2) -> Self { Self { initial, terminal } }
+
+    /// > 0 if the point is below the line, < 0 if above and 0 if collinear.
+    const fn relative_slope(&self, point: IVec2) -> i32 {
+        let delta = IVec2::new(
+            self.terminal.x - self.initial.x,
+            self.terminal.y - self.initial.y,
+        );
+        delta.y * (self.terminal.x - point.x) - delta.x * (self.terminal.y - point.y)
+    }
+
+    pub const fn is_below(&self, point: IVec2) -> bool { self.relative_slope(point) > 0 }
+
+    pub const fn is_below_or_collinear(&self, point: IVec2) -> bool { self.relative_slope(point) >= 0 }
+
+    pub const fn is_above(&self, point: IVec2) -> bool { self.relative_slope(point) < 0 }
+
+    pub const fn is_above_or_collinear(&self, point: IVec2) -> bool { self.relative_slope(point) <= 0 }
+
+    pub const fn is_collinear(&self, point: IVec2) -> bool { self.relative_slope(point) == 0 }
+
+    pub const fn is_line_collinear(&self, other: &Self) -> bool {
+        self.is_collinear(other.initial) && self.is_collinear(other.terminal)
+    }
+}
@@ -0,0 +1,74 @@
+use super::sight_line::*;
+use crate::prelude::*;
+
+/// A corner that bent one of the lines of a `View`.
+/// Bumps form linked lists through `parent`, stored in a shared arena
+/// so copies of a `View` can share their history.
+pub struct ViewBump {
+    pub point: IVec2,
+    pub parent: Option<usize>,
+}
+
+/// The area between two lines that is still visible from the origin.
+#[derive(Clone, Copy)]
+pub struct View {
+    pub shallow_line: SightLine,
+    pub steep_line: SightLine,
+    pub shallow_bump: Option<usize>,
+    pub steep_bump: Option<usize>,
+}
+
+impl View {
+    pub const fn new(shallow_line: SightLine, steep_line: SightLine) -> Self {
+        Self {
+            shallow_line,
+            steep_line,
+            shallow_bump: None,
+            steep_bump: None,
+        }
+    }
+
+    /// Pull the shallow line up over an opaque tile's corner.
+    pub fn add_shallow_bump(&mut self, point: IVec2, bumps: &mut Vec<ViewBump>) {
+        self.shallow_line.terminal = point;
+        bumps.push(ViewBump {
+            point,
+            parent: self.shallow_bump,
+        });
+        self.shallow_bump = Some(bumps.len() - 1);
+
+        let mut current = self.steep_bump;
+        while let Some(index) = current {
+            if self.shallow_line.is_above(bumps[index].point) {
+                self.shallow_line.initial = bumps[index].point;
+            }
+            current = bumps[index].parent;
+        }
+    }
+
+    /// Push the steep line down under an opaque tile's corner.
+    pub fn add_steep_bump(&mut self, point: IVec2, bumps: &mut Vec<ViewBump>) {
+        self.steep_line.terminal = point;
+        bumps.push(ViewBump {
+            point,
+            parent: self.steep_bump,
+        });
+        self.steep_bump = Some(bumps.len() - 1);
+
+        let mut current = self.shallow_bump;
+        while let Some(index) = current {
+            if self.steep_line.is_below(bumps[index].point) {
+                self.steep_line.initial = bumps[index].point;
+            }
+            current = bumps[index].parent;
+        }
+    }
+
+    /// A view is dead once both lines lie on top of each other
+    /// and pass through one of the origin's far corners.
+    pub fn is_valid(&self) -> bool {
+        !(self.shallow_line.is_line_collinear(&self.steep_line) &&
+            (self.shallow_line.is_collinear(IVec2::new(0, 1)) ||
+                self.shallow_line.is_collinear(IVec2::new(1, 0))))
+    }
+}
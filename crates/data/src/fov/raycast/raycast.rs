@@ -0,0 +1,85 @@
+use crate::prelude::*;
+
+/// Casts a Bresenham ray from the origin to every tile on the edge of the range.
+/// Much cheaper than shadowcasting, but walls can have gaps and it isn't symmetric.
+pub struct Raycast;
+
+impl<'w, 's> FovAlgorithm<'w, 's> for Raycast {
+    fn compute_fov(
+        origin: Position,
+        vision_type: u8,
+        range: u32,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+        receiver: &mut impl FovReceiver,
+    ) {
+        receiver.set_visible(origin);
+
+        let range = range as i32;
+        for i in -range..=range {
+            for end in [
+                IVec2::new(i, range),
+                IVec2::new(i, -range),
+                IVec2::new(range, i),
+                IVec2::new(-range, i),
+            ] {
+                Self::cast_ray(
+                    origin,
+                    vision_type,
+                    range,
+                    end,
+                    provider,
+                    q_blocks_vision,
+                    receiver,
+                );
+            }
+        }
+    }
+}
+
+impl<'w, 's> Raycast {
+    fn cast_ray(
+        origin: Position,
+        vision_type: u8,
+        range: i32,
+        end: IVec2,
+        provider: &mut impl FovProvider,
+        q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
+        receiver: &mut impl FovReceiver,
+    ) {
+        // skip the origin
+        for tile in BresenhamLineInclusiveIter::new(IVec2::ZERO, end).skip(1) {
+            // compare the squares, it's faster!
+            if DistanceAlg::PythagorasSquared.distance2d(IVec2::ZERO, tile) > range.pow(2) as f32 {
+                return;
+            }
+
+            let position = origin + tile;
+            receiver.set_visible(position);
+            if provider.is_opaque(position, vision_type, q_blocks_vision) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pillars_hide_what_is_behind_them() {
+        let origin = test_position(8, 8);
+        let pillar = test_position(10, 8);
+        let visibility_map = test_fov(Fov::Raycast, origin, 6, &[pillar]);
+
+        assert!(visibility_map.get_visible(origin));
+        assert!(visibility_map.get_visible(pillar));
+        assert!(!visibility_map.get_visible(test_position(12, 8)));
+        assert!(!visibility_map.get_visible(test_position(14, 8)));
+        // Rays in every other direction carry on to the edge of the range.
+        assert!(visibility_map.get_visible(test_position(2, 8)));
+        assert!(visibility_map.get_visible(test_position(8, 14)));
+        assert!(!visibility_map.get_visible(test_position(8, 15)));
+    }
+}
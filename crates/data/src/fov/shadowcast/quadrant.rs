@@ -29,8 +29,8 @@ impl<'a, 'w, 's> Quadrant<'a, 'w, 's> {
     // adjust the transform based on which direction we are scanning
     fn transform(&self, tile: IVec2) -> Position {
         let offset = match self.direction {
-            CardinalDirection::North => IVec2::new(tile.y, -tile.x),
-            CardinalDirection::South => IVec2::new(tile.y, tile.x),
+            CardinalDirection::North => IVec2::new(tile.y, tile.x),
+            CardinalDirection::South => IVec2::new(tile.y, -tile.x),
             CardinalDirection::East => IVec2::new(tile.x, tile.y),
             CardinalDirection::West => IVec2::new(-tile.x, tile.y),
         };
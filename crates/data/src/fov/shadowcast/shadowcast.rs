@@ -91,3 +91,30 @@ impl<'w, 's> Shadowcast {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn north_scans_towards_positive_y() {
+        let origin = test_position(8, 8);
+        let wall = test_position(8, 9);
+        let north = Fov::ShadowcastDirection(CardinalDirection::North);
+
+        let open = test_fov(north, origin, 6, &[]);
+        assert!(open.get_visible(test_position(8, 12)));
+        assert!(!open.get_visible(test_position(8, 4)));
+
+        // A wall just north of the origin shadows the tiles behind it.
+        let walled = test_fov(north, origin, 6, &[wall]);
+        assert!(walled.get_visible(wall));
+        assert!(!walled.get_visible(test_position(8, 10)));
+        assert!(!walled.get_visible(test_position(8, 12)));
+
+        // Looking all the way around, the south side is unaffected.
+        let all_around = test_fov(Fov::Shadowcast, origin, 6, &[wall]);
+        assert!(!all_around.get_visible(test_position(8, 12)));
+        assert!(all_around.get_visible(test_position(8, 4)));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::prelude::*;
+
+/// `FovProvider` for tests where only the listed positions are opaque.
+pub struct TestWalls(pub HashSet<Position>);
+
+impl FovProvider for TestWalls {
+    fn is_opaque(
+        &mut self,
+        position: Position,
+        _vision_type: u8,
+        _q_blocks_vision: &Query<&BlocksVision>,
+    ) -> bool {
+        self.0.contains(&position)
+    }
+}
+
+pub fn test_position(x: u32, y: u32) -> Position {
+    Position::new(
+        WorldPosition::ZERO,
+        LocalPosition::new(x, y, MapLayer::Terrain as u32),
+    )
+}
+
+/// Runs `fov` from `origin` with only `walls` blocking the view.
+pub fn test_fov(fov: Fov, origin: Position, range: u32, walls: &[Position]) -> VisibilityMap {
+    let mut world = World::new();
+    let mut system_state: SystemState<Query<&BlocksVision>> = SystemState::new(&mut world);
+    let q_blocks_vision = system_state.get(&world);
+
+    let mut provider = TestWalls(walls.iter().copied().collect());
+    let mut visibility_map = VisibilityMap::new(UVec2::new(32, 32));
+    fov.compute(
+        origin,
+        VisionType::Normal.as_u8(),
+        range,
+        &mut provider,
+        &q_blocks_vision,
+        &mut visibility_map,
+    );
+    visibility_map
+}
+
+/// Checks that wherever `fov` sees one floor tile from another, it also sees back the other
+/// way, among randomly scattered walls.
+pub fn assert_symmetric(fov: Fov) {
+    for seed in 0..4 {
+        let mut random = Random::new(seed);
+        let (mut walls, mut floor) = (Vec::new(), Vec::new());
+        for y in 8..20 {
+            for x in 8..20 {
+                if random.prng.max(100) < 20 {
+                    walls.push(test_position(x, y));
+                } else {
+                    floor.push(test_position(x, y));
+                }
+            }
+        }
+
+        let views: HashMap<Position, VisibilityMap> =
+            floor.iter().map(|&origin| (origin, test_fov(fov, origin, 6, &walls))).collect();
+        for from in &floor {
+            for to in &floor {
+                assert_eq!(
+                    views[from].get_visible(*to),
+                    views[to].get_visible(*from),
+                    "{:?} with seed {} between {} and {}",
+                    fov,
+                    seed,
+                    from,
+                    to
+                );
+            }
+        }
+    }
+}
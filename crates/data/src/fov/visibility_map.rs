@@ -1,23 +1,153 @@
 use crate::prelude::*;
 
-#[derive(Default, Debug, Clone)]
+/// Visible tiles, stored as one bit per tile.
+///
+/// Each map the fov reaches gets its own `BitGrid` of `map_size`, so lookups are a single bit
+/// test. The `MapLayer` of a `Position` is ignored. The default is sized for the game's maps.
+#[derive(Debug, Clone)]
 pub struct VisibilityMap {
-    visible_positions: HashSet<Position>,
+    map_size: UVec2,
+    maps: HashMap<WorldPosition, BitGrid>,
+}
+
+impl Default for VisibilityMap {
+    fn default() -> Self { Self::new(UVec2::new(GRID_WIDTH, GRID_HEIGHT)) }
 }
 
 impl VisibilityMap {
+    /// `map_size` is the size of the maps being looked at.
     #[inline(always)]
-    pub fn new() -> Self {
+    pub fn new(map_size: UVec2) -> Self {
         Self {
-            visible_positions: HashSet::new(),
+            map_size,
+            maps: HashMap::new(),
+        }
+    }
+
+    /// The visible tiles on a single map, if any are visible.
+    pub fn get_map(&self, world_position: WorldPosition) -> Option<&BitGrid> {
+        self.maps.get(&world_position)
+    }
+
+    /// Iterates every visible `Position` without allocating.
+    pub fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        self.maps.iter().flat_map(|(&world_position, grid)| {
+            grid.cells.iter_ones().map(move |index| {
+                let point = grid.index_to_pt_unchecked(index);
+                Position::new(
+                    world_position,
+                    LocalPosition::new(point.x as u32, point.y as u32, MapLayer::Terrain as u32),
+                )
+            })
+        })
+    }
+
+    pub fn len(&self) -> usize { self.maps.values().map(|grid| grid.cells.count_ones()).sum() }
+
+    pub fn is_empty(&self) -> bool { self.maps.values().all(|grid| grid.cells.not_any()) }
+
+    /// Only keep the positions for which `f` returns `true`.
+    pub fn retain(&mut self, mut f: impl FnMut(&Position) -> bool) {
+        for (&world_position, grid) in self.maps.iter_mut() {
+            let width = grid.size.x as usize;
+            for (index, mut visible) in grid.cells.iter_mut().enumerate() {
+                if !*visible {
+                    continue;
+                }
+
+                let local_position = LocalPosition::new(
+                    (index % width) as u32,
+                    (index / width) as u32,
+                    MapLayer::Terrain as u32,
+                );
+                if !f(&Position::new(world_position, local_position)) {
+                    visible.set(false);
+                }
+            }
         }
+        self.maps.retain(|_, grid| grid.cells.any());
     }
 }
 
 impl FovReceiver for VisibilityMap {
-    fn get_visible(&self, position: Position) -> bool { self.visible_positions.contains(&position) }
+    fn get_visible(&self, position: Position) -> bool {
+        self.maps
+            .get(&position.get_world_position())
+            .and_then(|grid| grid.get(position.gridpoint()))
+            .map_or(false, |visible| *visible)
+    }
+
+    fn set_visible(&mut self, position: Position) {
+        self.maps
+            .entry(position.get_world_position())
+            .or_insert_with(|| BitGrid::new_default(self.map_size))
+            .set(position.gridpoint(), true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(world_x: i32, x: u32, y: u32) -> Position {
+        Position::new(
+            WorldPosition::new(world_x, 0, 0),
+            LocalPosition::new(x, y, MapLayer::Terrain as u32),
+        )
+    }
+
+    #[test]
+    fn keeps_each_map_apart() {
+        let mut visibility_map = VisibilityMap::new(UVec2::new(10, 6));
+        let visible = [position(0, 9, 5), position(0, 0, 0), position(1, 0, 5)];
+        for position in visible {
+            visibility_map.set_visible(position);
+        }
 
-    fn set_visible(&mut self, position: Position) { self.visible_positions.insert(position); }
+        assert!(visible.iter().all(|&position| visibility_map.get_visible(position)));
+        assert!(!visibility_map.get_visible(position(1, 9, 5)));
+        assert!(!visibility_map.get_visible(position(2, 0, 0)));
+        assert_eq!(
+            visibility_map.get_map(WorldPosition::new(1, 0, 0)).unwrap().size,
+            UVec2::new(10, 6)
+        );
 
-    fn get_all(&self) -> HashSet<Position> { self.visible_positions.clone() }
+        let mut seen: Vec<Position> = visibility_map.iter().collect();
+        seen.sort_by_key(|position| {
+            (
+                position.get_world_position().x(),
+                position.x(),
+                position.y(),
+            )
+        });
+        assert_eq!(seen, vec![visible[1], visible[0], visible[2]]);
+        assert_eq!(visibility_map.len(), 3);
+    }
+
+    #[test]
+    fn retain_drops_emptied_maps() {
+        let mut visibility_map = VisibilityMap::new(UVec2::new(10, 6));
+        visibility_map.set_visible(position(0, 3, 3));
+        visibility_map.set_visible(position(0, 4, 3));
+        visibility_map.set_visible(position(1, 3, 3));
+
+        visibility_map.retain(|position| position.x() == 3 && position.get_world_position().x() == 0);
+        assert!(visibility_map.get_visible(position(0, 3, 3)));
+        assert!(!visibility_map.get_visible(position(0, 4, 3)));
+        assert!(visibility_map.get_map(WorldPosition::new(1, 0, 0)).is_none());
+        assert_eq!(visibility_map.len(), 1);
+    }
+
+    #[test]
+    fn default_covers_a_whole_map() {
+        let mut visibility_map = VisibilityMap::default();
+        let corner = position(0, GRID_WIDTH - 1, GRID_HEIGHT - 1);
+        visibility_map.set_visible(corner);
+
+        assert!(visibility_map.get_visible(corner));
+        assert_eq!(
+            visibility_map.get_map(WorldPosition::ZERO).unwrap().size,
+            UVec2::new(GRID_WIDTH, GRID_HEIGHT)
+        );
+    }
 }
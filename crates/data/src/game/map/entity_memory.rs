@@ -0,0 +1,25 @@
+use crate::prelude::*;
+
+/// How faded a remembered entity is drawn.
+pub const GHOST_ALPHA: f32 = 0.4;
+
+/// What an entity looked like the last time it was seen.
+#[derive(Debug, Clone)]
+pub struct EntityMemory {
+    pub entity: Entity,
+    pub sprite: TextureAtlasSprite,
+    pub texture_atlas: Handle<TextureAtlas>,
+    /// The sprite drawn in place of the entity while it's out of view.
+    pub ghost: Option<Entity>,
+}
+
+impl EntityMemory {
+    pub fn new(entity: Entity, sprite: &TextureAtlasSprite, texture_atlas: &Handle<TextureAtlas>) -> Self {
+        Self {
+            entity,
+            sprite: sprite.clone(),
+            texture_atlas: texture_atlas.clone(),
+            ghost: None,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::prelude::*;
+
+/// Number of sprite variants in a "blob" auto-tile set.
+pub const AUTO_TILE_VARIANTS: usize = 47;
+
+/// Maps every raw 8-neighbour bitmap onto its blob variant `0..AUTO_TILE_VARIANTS`.
+const AUTO_TILE_TABLE: [u8; 256] = build_auto_tile_table();
+
+/// Drop any ordinal neighbour that isn't backed by both of its adjacent cardinals.
+/// A corner only changes the look of a tile when both of its sides connect.
+pub const fn reduce_auto_tile_bitmap(bitmap: DirectionBitmap) -> DirectionBitmap {
+    let raw = bitmap.raw;
+    let mut reduced = raw & ALL_CARDINAL_DIRECTION_BITMAP_RAW;
+
+    let corners = [
+        (
+            GridDirection::NorthEast,
+            GridDirection::North,
+            GridDirection::East,
+        ),
+        (
+            GridDirection::SouthEast,
+            GridDirection::South,
+            GridDirection::East,
+        ),
+        (
+            GridDirection::SouthWest,
+            GridDirection::South,
+            GridDirection::West,
+        ),
+        (
+            GridDirection::NorthWest,
+            GridDirection::North,
+            GridDirection::West,
+        ),
+    ];
+
+    let mut i = 0;
+    while i < corners.len() {
+        let (corner, a, b) = corners[i];
+        if raw & corner.bitmap_raw() != 0 && raw & a.bitmap_raw() != 0 && raw & b.bitmap_raw() != 0 {
+            reduced |= corner.bitmap_raw();
+        }
+        i += 1;
+    }
+
+    DirectionBitmap::new(reduced)
+}
+
+/// Returns the blob variant `0..AUTO_TILE_VARIANTS` for an 8-neighbour bitmap.
+pub const fn auto_tile_variant(bitmap: DirectionBitmap) -> usize {
+    AUTO_TILE_TABLE[bitmap.raw as usize] as usize
+}
+
+const fn build_auto_tile_table() -> [u8; 256] {
+    // Number each distinct reduced bitmap in ascending order.
+    let mut variants = [0u8; 256];
+    let mut next = 0;
+    let mut raw = 0;
+    while raw < 256 {
+        if reduce_auto_tile_bitmap(DirectionBitmap::new(raw as u8)).raw == raw as u8 {
+            variants[raw] = next;
+            next += 1;
+        }
+        raw += 1;
+    }
+
+    // Point every raw bitmap at the variant of its reduced form.
+    let mut table = [0u8; 256];
+    let mut raw = 0;
+    while raw < 256 {
+        table[raw] = variants[reduce_auto_tile_bitmap(DirectionBitmap::new(raw as u8)).raw as usize];
+        raw += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blob_has_47_variants() {
+        let mut seen = HashSet::new();
+        for raw in 0..=u8::MAX {
+            seen.insert(auto_tile_variant(DirectionBitmap::new(raw)));
+        }
+        assert_eq!(seen.len(), AUTO_TILE_VARIANTS);
+        assert!(seen.iter().all(|&v| v < AUTO_TILE_VARIANTS));
+    }
+
+    #[test]
+    fn lone_corners_are_ignored() {
+        let corner = GridDirection::NorthEast.bitmap();
+        assert_eq!(
+            auto_tile_variant(corner),
+            auto_tile_variant(DirectionBitmap::empty())
+        );
+
+        let backed = corner | GridDirection::North.bitmap() | GridDirection::East.bitmap();
+        assert_ne!(
+            auto_tile_variant(backed),
+            auto_tile_variant(GridDirection::North.bitmap() | GridDirection::East.bitmap())
+        );
+    }
+}
@@ -1,5 +1,11 @@
 use crate::prelude::*;
 
+/// Anything dimmer than this is too dark for `VisionType::Normal`.
+pub const LIGHT_THRESHOLD: f32 = 0.1;
+
+/// Ambient light of a generated level. Below `LIGHT_THRESHOLD`, so only lit tiles can be seen.
+pub const DEFAULT_AMBIENT_LIGHT: Color = Color::rgb(0.02, 0.02, 0.03);
+
 // This needs to impl FromWorld not derive reflect
 pub struct Map {
     // Map Definitions
@@ -7,11 +13,17 @@ pub struct Map {
     pub size: UVec2,
     pub world_position: WorldPosition,
     pub random: Random,
+    pub exits: Vec<MapExit>,
 
     // Update Flags
     pub update_all: bool,
     pub update_tiles: HashSet<UVec2>,
     pub explored_tiles: HashSet<UVec2>,
+    pub remembered_entities: HashMap<UVec2, EntityMemory>,
+
+    // Lighting
+    pub ambient_light: Color,
+    pub light: Grid<Color>,
 
     // Object containers
     pub terrain: Grid<TerrainType>,
@@ -22,10 +34,137 @@ pub struct Map {
 
 // Constructor: See MapPassThroughData
 
-//// Perform terrain functions on this map
-// impl Map {
-//
-//}
+// Perform terrain functions on this map
+impl Map {
+    /// Do not use this function!!!
+    /// Use MapManager::set_terrain instead!!!
+    pub fn set_terrain(&mut self, position: LocalPosition, terrain_type: TerrainType) -> bool {
+        let point = position.gridpoint();
+        if self.terrain.set(point, terrain_type).is_none() {
+            return false;
+        }
+
+        // Neighbours need to pick a new auto-tile variant too.
+        self.update_tiles.insert(point);
+        for direction in GridDirection::all() {
+            let neighbour = point.as_ivec2() + direction.coord();
+            if self.terrain.in_bounds(neighbour) {
+                self.update_tiles.insert(neighbour.as_uvec2());
+            }
+        }
+
+        true
+    }
+
+    /// Do not use this function!!!
+    /// Use MapManager::get_terrain instead!!!
+    pub fn get_terrain(&self, position: LocalPosition) -> Option<TerrainType> {
+        self.terrain.get(position.gridpoint()).copied()
+    }
+
+    /// Bitmap of the 8 neighbours sharing this tile's terrain.
+    /// Neighbours off the edge of the map count as matching so
+    /// walls run cleanly into the border.
+    pub fn terrain_bitmap(&self, point: UVec2) -> DirectionBitmap {
+        let Some(&terrain_type) = self.terrain.get(point) else { return DirectionBitmap::empty(); };
+
+        let mut bitmap = DirectionBitmap::empty();
+        for direction in GridDirection::all() {
+            let neighbour = point.as_ivec2() + direction.coord();
+            match self.terrain.get(neighbour) {
+                Some(&other) if other != terrain_type => {},
+                _ => bitmap |= direction.bitmap(),
+            }
+        }
+        bitmap
+    }
+
+    /// Tile id for the auto-tiled terrain at `point` on the features layer.
+    ///
+    /// Returns `None` if the terrain isn't auto-tiled.
+    pub fn auto_tile_id(&self, point: UVec2) -> Option<usize> {
+        let start = self.terrain.get(point)?.auto_tile_start_id()?;
+        Some(start + auto_tile_variant(self.terrain_bitmap(point)))
+    }
+}
+
+// Perform lighting functions on this map
+impl Map {
+    /// Do not use this function!!!
+    /// Use MapManager::clear_lights instead!!!
+    pub fn clear_lights(&mut self) { self.light.cells.fill(Color::BLACK); }
+
+    /// Do not use this function!!!
+    /// Use MapManager::add_light instead!!!
+    pub fn add_light(&mut self, position: LocalPosition, color: Color) {
+        let Some(light) = self.light.get_mut(position.gridpoint()) else { return; };
+
+        *light = Color::rgb(
+            light.r() + color.r(),
+            light.g() + color.g(),
+            light.b() + color.b(),
+        );
+    }
+
+    /// Ambient light plus every light source shining on `point`.
+    ///
+    /// Anything brighter than white is scaled back down so the tint keeps its hue.
+    pub fn light_at(&self, point: UVec2) -> Color {
+        let light = self.light.get(point).copied().unwrap_or(Color::BLACK);
+        let light = Vec3::new(
+            self.ambient_light.r() + light.r(),
+            self.ambient_light.g() + light.g(),
+            self.ambient_light.b() + light.b(),
+        );
+        let light = light / light.max_element().max(1.0);
+        Color::rgb(light.x, light.y, light.z)
+    }
+
+    /// Is there enough light at `point` for normal vision?
+    pub fn is_lit(&self, point: UVec2) -> bool {
+        let light = self.light_at(point);
+        light.r().max(light.g()).max(light.b()) >= LIGHT_THRESHOLD
+    }
+
+    /// Can something with `vision_type` see the tile at `point`, going by the light on it?
+    pub fn is_visible_to(&self, point: UVec2, vision_type: u8) -> bool {
+        !VisionType::needs_light(vision_type) || self.is_lit(point)
+    }
+}
+
+// Perform memory functions on this map
+impl Map {
+    /// Do not use this function!!!
+    /// Use MapManager::remember_entity instead!!!
+    ///
+    /// Returns the memories replaced, so their ghosts can be despawned.
+    pub fn remember_entity(&mut self, position: LocalPosition, memory: EntityMemory) -> Vec<EntityMemory> {
+        let point = position.gridpoint();
+        if !self.terrain.in_bounds(point) {
+            return Vec::new();
+        }
+
+        // An entity can only be remembered in one place.
+        let mut forgotten = Vec::new();
+        self.remembered_entities.retain(|_, remembered| {
+            if remembered.entity == memory.entity {
+                forgotten.push(remembered.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        forgotten.extend(self.remembered_entities.insert(point, memory));
+        forgotten
+    }
+
+    /// Do not use this function!!!
+    /// Use MapManager::forget_visible_entities instead!!!
+    pub fn forget_entity(&mut self, position: LocalPosition) -> Option<EntityMemory> {
+        self.remembered_entities.remove(&position.gridpoint())
+    }
+}
 
 // Perform actor functions on this map
 impl Map {
@@ -184,3 +323,42 @@ impl Map {
 
 // Map Systems
 impl Map {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dark_map() -> Map {
+        let user_data = MapPassThroughData {
+            map_entity: Entity::from_raw(0),
+            world_position: WorldPosition::ZERO,
+            ambient_light: DEFAULT_AMBIENT_LIGHT,
+        };
+        let size = UVec2::new(8, 8);
+        Map::from(MapGenerator::new(size, Random::new(0), SetBuilder::new(), user_data).generate())
+    }
+
+    #[test]
+    fn unlit_tiles_need_infared() {
+        let mut map = dark_map();
+        let point = UVec2::new(3, 3);
+        assert!(!map.is_visible_to(point, VisionType::Normal.as_u8()));
+        assert!(map.is_visible_to(point, VisionType::Infared.as_u8()));
+
+        let position = LocalPosition::new(3, 3, MapLayer::Terrain as u32);
+        map.add_light(position, Color::rgb(0.5, 0.4, 0.3));
+        assert!(map.is_visible_to(point, VisionType::Normal.as_u8()));
+    }
+
+    #[test]
+    fn bright_light_keeps_its_hue() {
+        let mut map = dark_map();
+        let position = LocalPosition::new(1, 1, MapLayer::Terrain as u32);
+        map.add_light(position, Color::rgb(1.0, 0.5, 0.0));
+        map.add_light(position, Color::rgb(1.0, 0.5, 0.0));
+
+        let light = map.light_at(UVec2::new(1, 1));
+        assert!((light.r() - 1.0).abs() < f32::EPSILON);
+        assert!(light.g() > 0.45 && light.g() < 0.55);
+    }
+}
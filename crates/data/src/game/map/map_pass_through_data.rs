@@ -3,6 +3,7 @@ use crate::prelude::*;
 pub struct MapPassThroughData {
     pub map_entity: Entity,
     pub world_position: WorldPosition,
+    pub ambient_light: Color,
     // TODO: Explored tiles should be passed from serialized data for the map on loading, or just a
     // new HashSet pub explored_tiles: HashSet<UVec2>
 }
@@ -22,11 +23,16 @@ impl From<MapGenData<MapPassThroughData>> for Map {
             size: data.size,
             world_position: data.user_data.world_position,
             random: data.random,
+            exits: data.exit_positions,
 
             update_all: true,
             update_tiles: HashSet::new(),
             // TODO: Add explored_tiles HashSet to MapPassThroughData for serialized data
             explored_tiles: HashSet::new(),
+            remembered_entities: HashMap::new(),
+
+            ambient_light: data.user_data.ambient_light,
+            light: Grid::new_copy(data.size, Color::BLACK),
 
             terrain: terrain_types,
             features: Grid::new_default(data.size),
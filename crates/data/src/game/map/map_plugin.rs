@@ -23,6 +23,13 @@ impl<T: StateNext> MapPlugin<T> {
 impl<T: StateNext> Plugin for MapPlugin<T> {
     fn build(&self, app: &mut App) {
         app.add_enter_system(self.state_construct, startup_map_manager)
+            .add_system_set_to_stage(
+                CoreStage::First,
+                ConditionSet::new()
+                    .run_in_state(self.state_running)
+                    .with_system(load_requested_map)
+                    .into(),
+            )
             .add_system_set_to_stage(
                 CoreStage::Last,
                 ConditionSet::new()
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use crate::prelude::*;
 
 /// MapManager SystemParam used for interacting with the maps.
@@ -172,20 +174,308 @@ impl<'w, 's> MapManager<'w, 's> {
 //    }
 //}
 
+// Perform terrain functions on maps
+impl<'w, 's> MapManager<'w, 's> {
+    /// Changes the terrain at a `Position`, flagging the tile and its
+    /// neighbours so their auto-tile variants get recomputed.
+    ///
+    /// Returns `true` if the terrain was changed.
+    pub fn set_terrain(&mut self, position: Position, terrain_type: TerrainType) -> bool {
+        let Some(map) = self.get_map(position.get_world_position()) else { return false; };
+
+        if map.set_terrain(position.get_local_position(), terrain_type) {
+            self.map_manager.terrain_changes.push(position);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Every `Position` whose terrain changed since the last call.
+    /// Used to invalidate cached `Viewshed`s.
+    pub fn take_terrain_changes(&mut self) -> Vec<Position> {
+        std::mem::take(&mut self.map_manager.terrain_changes)
+    }
+
+    /// Same as `take_terrain_changes()`, but leaves them for the next caller.
+    pub fn get_terrain_changes(&self) -> &[Position] { &self.map_manager.terrain_changes }
+
+    /// Attempts to get the terrain at a `Position`
+    ///
+    /// Returns `Some(TerrainType)` if the `Position` is valid.
+    pub fn get_terrain(&mut self, position: Position) -> Option<TerrainType> {
+        let Some(map) = self.get_map(position.get_world_position()) else { return None; };
+
+        map.get_terrain(position.get_local_position())
+    }
+}
+
+// Perform memory functions on maps
+impl<'w, 's> MapManager<'w, 's> {
+    /// Remembers what `entity` looks like at `position`, so it can be drawn as a ghost
+    /// once `position` is out of view.
+    pub fn remember_entity(
+        &mut self,
+        entity: Entity,
+        position: Position,
+        sprite: &TextureAtlasSprite,
+        texture_atlas: &Handle<TextureAtlas>,
+    ) {
+        let Some(map) = self.get_loaded_map(position.get_world_position()) else { return; };
+
+        let memory = EntityMemory::new(entity, sprite, texture_atlas);
+        for forgotten in map.remember_entity(position.get_local_position(), memory) {
+            self.despawn_ghost(forgotten);
+        }
+    }
+
+    /// Forgets everything remembered on tiles which are in view,
+    /// whatever is there now will be remembered instead.
+    pub fn forget_visible_entities(&mut self) {
+        let visible: Vec<Position> = self.map_manager.visible_tiles.iter().collect();
+        for position in visible {
+            let Some(map) = self.get_loaded_map(position.get_world_position()) else { continue; };
+            let Some(forgotten) = map.forget_entity(position.get_local_position()) else { continue; };
+
+            self.despawn_ghost(forgotten);
+        }
+    }
+
+    /// Spawns a ghost sprite for every memory on the current map which is out of view.
+    pub fn spawn_ghosts(&mut self) {
+        let world_position = self.map_manager.current_map.0;
+        let resource = &mut *self.map_manager;
+        for (&point, memory) in resource.current_map.1.remembered_entities.iter_mut() {
+            let position = Position::new(
+                world_position,
+                LocalPosition::new(point.x, point.y, MapLayer::Actors as u32),
+            );
+            if memory.ghost.is_some() || resource.visible_tiles.get_visible(position) {
+                continue;
+            }
+
+            let mut sprite = memory.sprite.clone();
+            sprite.color.set_a(GHOST_ALPHA);
+            memory.ghost = Some(
+                self.commands
+                    .spawn((
+                        Name::new("Ghost"),
+                        Ghost { world_position },
+                        SpriteSheetBundle {
+                            sprite,
+                            texture_atlas: memory.texture_atlas.clone(),
+                            transform: Transform::from_translation(position.translation()),
+                            ..Default::default()
+                        },
+                    ))
+                    .id(),
+            );
+        }
+    }
+
+    /// Despawns every ghost on the map at `world_position`, they are spawned again from
+    /// what was remembered once it's the current map.
+    fn despawn_ghosts(&mut self, world_position: WorldPosition) {
+        let Some(map) = self.get_loaded_map(world_position) else { return; };
+
+        let ghosts: Vec<Entity> =
+            map.remembered_entities.values_mut().filter_map(|memory| memory.ghost.take()).collect();
+        for ghost in ghosts {
+            self.commands.entity(ghost).despawn();
+        }
+    }
+
+    fn despawn_ghost(&mut self, memory: EntityMemory) {
+        if let Some(ghost) = memory.ghost {
+            self.commands.entity(ghost).despawn();
+        }
+    }
+}
+
+// Perform lighting functions on maps
+impl<'w, 's> MapManager<'w, 's> {
+    /// Removes the light from every light source on all loaded maps.
+    /// Ambient light is left alone.
+    pub fn clear_lights(&mut self) {
+        self.map_manager.current_map.1.clear_lights();
+        for map in self.map_manager.loaded_maps.values_mut() {
+            map.clear_lights();
+        }
+    }
+
+    /// Shines `light_source` out from `origin`, light is blocked by
+    /// anything that blocks `VisionType::Normal`.
+    pub fn add_light<'a, 'b>(
+        &mut self,
+        origin: Position,
+        light_source: &LightSource,
+        q_blocks_vision: &Query<'a, 'b, &'static BlocksVision>,
+    ) {
+        let mut lit_positions = VisibilityMap::new(self.get_map_size());
+        Fov::Shadowcast.compute(
+            origin,
+            VisionType::Normal.as_u8(),
+            light_source.radius,
+            self,
+            q_blocks_vision,
+            &mut lit_positions,
+        );
+
+        for position in lit_positions.iter() {
+            let color = light_source.light_at(origin.offset_to(position));
+            let Some(map) = self.get_loaded_map(position.get_world_position()) else { continue; };
+
+            map.add_light(position.get_local_position(), color);
+        }
+    }
+
+    /// Sets the light level of a map when there are no light sources around.
+    pub fn set_ambient_light(&mut self, world_position: WorldPosition, color: Color) {
+        let Some(map) = self.get_map(world_position) else { return; };
+
+        map.ambient_light = color;
+    }
+
+    /// Attempts to get the light level at a `Position`
+    ///
+    /// Returns `Some(Color)` if the `Position` is valid.
+    pub fn get_light(&mut self, position: Position) -> Option<Color> {
+        let Some(map) = self.get_map(position.get_world_position()) else { return None; };
+
+        Some(map.light_at(position.gridpoint()))
+    }
+
+    /// Returns `true` if there is enough light at `Position` for normal vision.
+    /// Maps which aren't loaded yet are dark.
+    pub fn is_lit(&mut self, position: Position) -> bool {
+        let Some(map) = self.get_loaded_map(position.get_world_position()) else { return false; };
+
+        map.is_lit(position.gridpoint())
+    }
+
+    /// Removes every unlit `Position` from `visibility_map` if `vision_type` needs light.
+    /// The viewer can always see its own tile.
+    pub fn remove_unlit(&mut self, origin: Position, vision_type: u8, visibility_map: &mut VisibilityMap) {
+        if !VisionType::needs_light(vision_type) {
+            return;
+        }
+
+        visibility_map.retain(|&position| {
+            position == origin ||
+                self.get_loaded_map(position.get_world_position()).map_or(false, |map| {
+                    map.is_visible_to(position.gridpoint(), vision_type)
+                })
+        });
+    }
+
+    /// Has any terrain changed since the last call to `take_terrain_changes()`?
+    pub fn has_terrain_changes(&self) -> bool { !self.map_manager.terrain_changes.is_empty() }
+}
+
 // Map Manipulation / General
 impl<'w, 's> MapManager<'w, 's> {
+    /// Size of the maps, every map is generated the same size as the current one.
+    pub fn get_map_size(&self) -> UVec2 { self.map_manager.current_map.1.size }
+
     pub fn get_current_world_position(&self) -> WorldPosition {
         self.map_manager.current_map.0.clone()
     }
 
+    /// Marks every visible tile as explored on whichever map it belongs to,
+    /// including neighbouring maps when the fov crosses an edge.
     pub fn set_visibility(&mut self, visibility_map: VisibilityMap) {
-        for position in visibility_map.get_all().iter() {
-            let Some(map) = self.get_map(position.get_world_position()) else { return; };
+        for position in visibility_map.iter() {
+            let Some(map) = self.get_loaded_map(position.get_world_position()) else { continue; };
 
             map.explored_tiles.insert(position.gridpoint());
         }
         self.map_manager.visible_tiles = visibility_map;
     }
+
+    /// Tiles which have ever been seen on the map at `world_position`.
+    ///
+    /// Returns `None` if the map isn't loaded.
+    pub fn get_explored_tiles(&mut self, world_position: WorldPosition) -> Option<&HashSet<UVec2>> {
+        self.get_loaded_map(world_position).map(|map| &map.explored_tiles)
+    }
+
+    /// Has the tile at `position` ever been seen?
+    pub fn is_explored(&mut self, position: Position) -> bool {
+        self.get_explored_tiles(position.get_world_position())
+            .map_or(false, |explored| explored.contains(&position.gridpoint()))
+    }
+
+    /// Tiles which are currently visible on the map at `world_position`.
+    pub fn get_visible_tiles(&self, world_position: WorldPosition) -> Option<&BitGrid> {
+        self.map_manager.visible_tiles.get_map(world_position)
+    }
+
+    /// The nearest explored tile reachable from `origin` which borders unexplored space,
+    /// searching over explored tiles on `origin`'s map only.
+    ///
+    /// `origin` and any position in `skip` are never returned.
+    /// Returns `None` once there is nothing left to explore.
+    pub fn find_unexplored(
+        &mut self,
+        origin: Position,
+        movement_type: u8,
+        q_blocks_movement: &Query<&BlocksMovement>,
+        skip: &HashSet<Position>,
+    ) -> Option<Position> {
+        let world_position = origin.get_world_position();
+        // Only `origin`'s map is searched, so it can be borrowed once for the whole search.
+        let map: &Map = self.get_loaded_map(world_position)?;
+        let explored = &map.explored_tiles;
+
+        let mut visited = HashSet::new();
+        visited.insert(origin);
+        let mut open = VecDeque::from([origin]);
+        while let Some(position) = open.pop_front() {
+            let neighbours: Vec<Position> = GridDirection::all()
+                .map(|direction| position + direction.coord())
+                .filter(|neighbour| neighbour.get_world_position() == world_position)
+                .collect();
+
+            let borders_unexplored =
+                neighbours.iter().any(|neighbour| !explored.contains(&neighbour.gridpoint()));
+            if position != origin && !skip.contains(&position) && borders_unexplored {
+                return Some(position);
+            }
+
+            for neighbour in neighbours {
+                if explored.contains(&neighbour.gridpoint()) &&
+                    visited.insert(neighbour) &&
+                    map.can_place_actor(
+                        neighbour.get_local_position(),
+                        movement_type,
+                        q_blocks_movement,
+                    )
+                {
+                    open.push_back(neighbour);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Generates at most one of the maps the fov has peeked into.
+    /// Spreading these out keeps a single fov from generating every neighbour at once.
+    pub fn load_requested_map(&mut self) {
+        self.map_manager.newly_loaded_maps.clear();
+
+        let Some(&world_position) = self.map_manager.requested_maps.iter().next() else { return; };
+        self.map_manager.requested_maps.remove(&world_position);
+
+        if !self.is_map_loaded(world_position) {
+            self.load_map(world_position);
+            self.map_manager.newly_loaded_maps.push(world_position);
+        }
+    }
+
+    /// Was a map loaded by `load_requested_map()` this frame?
+    /// Anything that looked across the edge of that map needs to look again.
+    pub fn has_newly_loaded_maps(&self) -> bool { !self.map_manager.newly_loaded_maps.is_empty() }
 }
 
 // Internal MapManager Functions
@@ -199,6 +489,22 @@ impl<'w, 's> MapManager<'w, 's> {
         }
     }
 
+    /// Same as `get_map()`, but never loads or generates a map.
+    fn get_loaded_map(&mut self, world_position: WorldPosition) -> Option<&mut Map> {
+        if self.map_manager.current_map.0 == world_position {
+            Some(&mut self.map_manager.current_map.1)
+        } else {
+            self.map_manager.loaded_maps.get_mut(&world_position)
+        }
+    }
+
+    /// Queue a map to be loaded by `load_requested_map()`.
+    fn request_map(&mut self, world_position: WorldPosition) {
+        if !self.is_map_loaded(world_position) {
+            self.map_manager.requested_maps.insert(world_position);
+        }
+    }
+
     fn load_map(&mut self, world_position: WorldPosition) {
         if self.is_map_loaded(world_position) ||
             self.deserialize_map(world_position) ||
@@ -245,6 +551,8 @@ impl<'w, 's> MapManager<'w, 's> {
             let (pos, map) = std::mem::replace(&mut self.map_manager.current_map, (world_position, map));
             // Retain the old current map in loaded_maps.
             self.add_to_loaded_maps(pos, map);
+            // Only the current map is drawn, so the old map's ghosts go with it.
+            self.despawn_ghosts(pos);
         }
     }
 }
@@ -279,6 +587,37 @@ impl<'w, 's> MapManager<'w, 's> {
         (terrain_layer_entity, features_layer_entity)
     }
 
+    /// Tilemaps for the 8 maps around the current map, to draw what was explored past an edge.
+    fn internal_create_neighbour_tilemaps(
+        commands: &mut Commands,
+        tilesets: &Tilesets,
+    ) -> Vec<NeighbourLayers> {
+        let mut neighbour_layers = Vec::new();
+        for y in -1..=1 {
+            for x in -1..=1 {
+                let offset = IVec2::new(x, y);
+                if offset == IVec2::ZERO {
+                    continue;
+                }
+
+                let parent = create_tilemap_parent(commands, &format!("NEIGHBOUR_LAYERS ({x}, {y})"));
+                let translation = (offset * IVec2::new(GRID_WIDTH as i32, GRID_HEIGHT as i32)).as_vec2();
+                commands.entity(parent).insert(Transform::from_translation(translation.extend(0.0)));
+
+                let (terrain_layer, features_layer) = Self::internal_create_tilemaps(commands, tilesets);
+                commands.entity(parent).push_children(&[terrain_layer, features_layer]);
+                neighbour_layers.push(NeighbourLayers {
+                    offset,
+                    terrain_layer,
+                    features_layer,
+                    shown: None,
+                    was_visible: false,
+                });
+            }
+        }
+        neighbour_layers
+    }
+
     fn internal_create_map(
         commands: &mut Commands,
         game_context: &mut ResMut<GameContext>,
@@ -296,9 +635,12 @@ impl<'w, 's> MapManager<'w, 's> {
         let map_entity = commands.spawn_empty().id();
 
         // Create the map.
-        let map = Self::generate_map(map_size, random, MapPassThroughData {
+        // Exits are hashed with the world's Prht so neighbouring maps line up.
+        let world_prht = game_context.random.prht.clone();
+        let map = Self::generate_map(map_size, random, world_prht, MapPassThroughData {
             world_position,
             map_entity,
+            ambient_light: DEFAULT_AMBIENT_LIGHT,
         });
 
         // Build the map entity.
@@ -317,8 +659,13 @@ impl<'w, 's> MapManager<'w, 's> {
         map
     }
 
-    fn generate_map(size: UVec2, random: Random, user_data: MapPassThroughData) -> Map {
-        Map::from(MapGenerator::new(size, random, SetBuilder::new().set_value(1), user_data).generate())
+    fn generate_map(size: UVec2, random: Random, world_prht: Prht, user_data: MapPassThroughData) -> Map {
+        let world_position = user_data.world_position;
+        Map::from(
+            MapGenerator::new(size, random, SetBuilder::new().set_value(1), user_data)
+                .with(ExitBuilder::new(world_position, world_prht))
+                .generate(),
+        )
     }
 }
 
@@ -333,11 +680,13 @@ pub fn startup_map_manager(
     let world_position = WorldPosition::new(0, 0, 0);
     let map = MapManager::internal_create_map(&mut commands, &mut game_context, world_position);
     let (terrain_layer, features_layer) = MapManager::internal_create_tilemaps(&mut commands, &tilesets);
+    let neighbour_layers = MapManager::internal_create_neighbour_tilemaps(&mut commands, &tilesets);
     commands.insert_resource(MapManagerResource::new(
         world_position,
         map,
         terrain_layer,
         features_layer,
+        neighbour_layers,
     ));
 
     if let Some(next_state) = state.0.next() {
@@ -358,6 +707,8 @@ pub fn set_current_map_to_current_player(
     }
 }
 
+pub fn load_requested_map(mut map_manager: MapManager) { map_manager.load_requested_map(); }
+
 pub fn update_tilemaps(
     mut map_manager: MapManager,
     q_storage: Query<&TileStorage>,
@@ -394,18 +745,19 @@ pub fn update_tilemaps(
                     check_next.insert(UVec2::new(x, y));
                     continue;
                 };
-                let index = *map.terrain.get_unchecked(UVec2::new(x, y)) as u32;
-                tile_texture_index.0 = index;
+                tile_texture_index.0 = map.terrain.get_unchecked(UVec2::new(x, y)).terrain_tile_id() as u32;
 
                 // Update Features
                 let Some(entity) = feature_storage.get(&tile_pos) else {
                     check_next.insert(UVec2::new(x, y));
                     continue;
                 };
-                let Ok((mut _tile_texture_index, ..)) = q_tiles.get_mut(entity) else {
+                let Ok((mut tile_texture_index, ..)) = q_tiles.get_mut(entity) else {
                     check_next.insert(UVec2::new(x, y));
                     continue;
                 };
+                tile_texture_index.0 =
+                    map.auto_tile_id(UVec2::new(x, y)).unwrap_or(TILE_FEATURES_MISSING_ID) as u32;
                 let Some(list) = map.features.get_unchecked(UVec2::new(x, y)) else {
                     continue;
                 };
@@ -433,18 +785,18 @@ pub fn update_tilemaps(
                 check_next.insert(UVec2::new(point.x, point.y));
                 continue;
             };
-            let index = *map.terrain.get_unchecked(UVec2::new(point.x, point.y)) as u32;
-            tile_texture_index.0 = index;
+            tile_texture_index.0 = map.terrain.get_unchecked(point).terrain_tile_id() as u32;
 
             // Update Features
             let Some(entity) = feature_storage.get(&tile_pos) else {
                 check_next.insert(UVec2::new(point.x, point.y));
                 continue;
             };
-            let Ok((mut _tile_texture_index, ..)) = q_tiles.get_mut(entity) else {
+            let Ok((mut tile_texture_index, ..)) = q_tiles.get_mut(entity) else {
                 check_next.insert(UVec2::new(point.x, point.y));
                 continue;
             };
+            tile_texture_index.0 = map.auto_tile_id(point).unwrap_or(TILE_FEATURES_MISSING_ID) as u32;
             let Some(list) = map.features.get_unchecked(UVec2::new(point.x, point.y)) else {
                 continue;
             };
@@ -462,10 +814,10 @@ pub fn update_tilemaps(
         LocalPosition::new(0, 0, MapLayer::Terrain as u32), // MapLayer is ignored
     );
 
-    let visible_tiles = map_manager.map_manager.visible_tiles.get_all();
-
-    // refresh mutable reference for borrow checker...
-    let map = &mut map_manager.map_manager.current_map.1;
+    // Borrow through the resource once so the map and visible tiles can be borrowed together.
+    let resource = &mut *map_manager.map_manager;
+    let map = &mut resource.current_map.1;
+    let visible_tiles = &resource.visible_tiles;
 
     for y in 0..map.size.height() {
         position.set_y(y);
@@ -473,27 +825,145 @@ pub fn update_tilemaps(
             position.set_x(x);
             let tile_pos = TilePos::new(x, y);
             let is_explored = map.explored_tiles.contains(&UVec2::new(x, y));
+
+            // Visible tiles are tinted by the light on them, remembered tiles are faded out.
+            let mut color = Color::WHITE;
+            color.set_a(0.15);
+            if visible_tiles.get_visible(position) {
+                color = map.light_at(UVec2::new(x, y));
+                color.set_a(1.0);
+            }
+
             if let Some(entity) = terrain_storage.get(&tile_pos) {
                 if let Ok(mut visibility) = q_visibility.get_mut(entity) {
                     visibility.is_visible = is_explored;
                 }
                 if let Ok((_index, mut tile_visibility, mut tile_color)) = q_tiles.get_mut(entity) {
                     tile_visibility.0 = is_explored;
-                    tile_color.0.set_a(0.15);
-                    if visible_tiles.contains(&position) {
-                        tile_color.0.set_a(1.0);
-                    }
+                    tile_color.0 = color;
                 }
             }
 
             if let Some(entity) = feature_storage.get(&tile_pos) {
                 if let Ok(mut visibility) = q_visibility.get_mut(entity) {
-                    visibility.is_visible = map.explored_tiles.contains(&UVec2::new(x, y));
+                    visibility.is_visible = is_explored;
+                }
+                if let Ok((_index, mut tile_visibility, mut tile_color)) = q_tiles.get_mut(entity) {
+                    tile_visibility.0 = is_explored;
+                    tile_color.0 = color;
                 }
-                // tiles too
             }
         }
     }
+
+    // Draw what has been explored on the surrounding maps.
+    let current_world_position = resource.current_map.0;
+    for slot in resource.neighbour_layers.iter_mut() {
+        let xyz = current_world_position.xyz() + slot.offset.extend(0);
+        let world_position = WorldPosition::new(xyz.x, xyz.y, xyz.z);
+        let Ok(terrain_storage) = q_storage.get(slot.terrain_layer) else { continue; };
+        let Ok(feature_storage) = q_storage.get(slot.features_layer) else { continue; };
+
+        let Some(map) = resource.loaded_maps.get_mut(&world_position) else {
+            if slot.shown.take().is_some() {
+                hide_tiles(
+                    terrain_storage,
+                    feature_storage,
+                    &mut q_tiles,
+                    &mut q_visibility,
+                );
+            }
+            continue;
+        };
+
+        // Only redraw when the map changed, or tiles on it are (or just were) in view.
+        let is_visible = resource.visible_tiles.get_map(world_position).is_some();
+        if slot.shown == Some(world_position) &&
+            !is_visible &&
+            !slot.was_visible &&
+            map.update_tiles.is_empty()
+        {
+            continue;
+        }
+
+        draw_neighbour(
+            map,
+            world_position,
+            &resource.visible_tiles,
+            terrain_storage,
+            feature_storage,
+            &mut q_tiles,
+            &mut q_visibility,
+        );
+        // The whole map has been redrawn, it will be redrawn again once it's the current map.
+        map.update_tiles.clear();
+        slot.shown = Some(world_position);
+        slot.was_visible = is_visible;
+    }
+}
+
+/// Redraws every tile of a neighbouring map on its own layers.
+fn draw_neighbour(
+    map: &Map,
+    world_position: WorldPosition,
+    visible_tiles: &VisibilityMap,
+    terrain_storage: &TileStorage,
+    feature_storage: &TileStorage,
+    q_tiles: &mut Query<(&mut TileTextureIndex, &mut TileVisible, &mut TileColor)>,
+    q_visibility: &mut Query<&mut Visibility>,
+) {
+    let mut position = Position::new(
+        world_position,
+        LocalPosition::new(0, 0, MapLayer::Terrain as u32), // MapLayer is ignored
+    );
+
+    for y in 0..map.size.height() {
+        position.set_y(y);
+        for x in 0..map.size.width() {
+            position.set_x(x);
+            let point = UVec2::new(x, y);
+            let tile_pos = TilePos::new(x, y);
+            let is_explored = map.explored_tiles.contains(&point);
+
+            let mut color = Color::WHITE;
+            color.set_a(0.15);
+            if visible_tiles.get_visible(position) {
+                color = map.light_at(point);
+                color.set_a(1.0);
+            }
+
+            let terrain_id = map.terrain.get_unchecked(point).terrain_tile_id() as u32;
+            let feature_id = map.auto_tile_id(point).unwrap_or(TILE_FEATURES_MISSING_ID) as u32;
+            for (storage, tile_id) in [(terrain_storage, terrain_id), (feature_storage, feature_id)] {
+                let Some(entity) = storage.get(&tile_pos) else { continue; };
+                if let Ok(mut visibility) = q_visibility.get_mut(entity) {
+                    visibility.is_visible = is_explored;
+                }
+                if let Ok((mut index, mut tile_visibility, mut tile_color)) = q_tiles.get_mut(entity) {
+                    index.0 = tile_id;
+                    tile_visibility.0 = is_explored;
+                    tile_color.0 = color;
+                }
+            }
+        }
+    }
+}
+
+/// Hides the layers of a neighbouring map which isn't loaded.
+fn hide_tiles(
+    terrain_storage: &TileStorage,
+    feature_storage: &TileStorage,
+    q_tiles: &mut Query<(&mut TileTextureIndex, &mut TileVisible, &mut TileColor)>,
+    q_visibility: &mut Query<&mut Visibility>,
+) {
+    for entity in terrain_storage.iter().chain(feature_storage.iter()).flatten() {
+        if let Ok(mut visibility) = q_visibility.get_mut(*entity) {
+            visibility.is_visible = false;
+        }
+        if let Ok((_index, mut tile_visibility, _color)) = q_tiles.get_mut(*entity) {
+            tile_visibility.0 = false;
+        }
+    }
 }
 
 // Implement FovProvider
@@ -504,6 +974,13 @@ impl<'w, 's> FovProvider for MapManager<'w, 's> {
         vision_type: u8,
         q_blocks_vision: &Query<&BlocksVision>,
     ) -> bool {
+        // Don't generate maps just to look at them, treat them as a wall until they are loaded.
+        let world_position = position.get_world_position();
+        if !self.is_map_loaded(world_position) {
+            self.request_map(world_position);
+            return true;
+        }
+
         if let Some(actors) = self.get_actors(position) {
             for &entity in actors {
                 if let Ok(blocks_vision) = q_blocks_vision.get(entity) {
@@ -528,10 +1005,38 @@ impl<'w, 's> FovProvider for MapManager<'w, 's> {
     }
 }
 
+// Implement ProjectileProvider
+impl<'w, 's> ProjectileProvider for MapManager<'w, 's> {
+    fn get_blocking_entity(
+        &mut self,
+        position: Position,
+        movement_type: u8,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Option<Entity> {
+        // Don't generate maps just to shoot into them.
+        if !self.is_map_loaded(position.get_world_position()) {
+            return None;
+        }
+
+        // Same as `Map::is_blocked`, actors first and then features.
+        let blocks = |entity: &&Entity| {
+            q_blocks_movement.get(**entity).map_or(false, |blocks_movement| {
+                blocks_movement.is_blocked(movement_type)
+            })
+        };
+        let actor = self.get_actors(position).and_then(|actors| actors.iter().find(blocks).copied());
+        actor.or_else(|| {
+            self.get_features(position).and_then(|features| features.iter().find(blocks).copied())
+        })
+    }
+}
+
 // Implement PathProvider
 impl<'w, 's> PathProvider for MapManager<'w, 's> {
     fn cost(&mut self, _position: Position, _movement_type: u8) -> u32 { 1 }
 
+    fn has_uniform_cost(&mut self, _movement_type: u8) -> bool { true }
+
     fn is_walkable(
         &mut self,
         position: Position,
@@ -547,3 +1052,21 @@ impl<'w, 's> PathProvider for MapManager<'w, 's> {
         )
     }
 }
+
+// Implement MapGraphProvider
+impl<'w, 's> MapGraphProvider for MapManager<'w, 's> {
+    fn get_exits(&mut self, world_position: WorldPosition) -> Vec<MapExit> {
+        if let Some(map) = self.get_loaded_map(world_position) {
+            return map.exits.clone();
+        }
+
+        ExitBuilder::<MapPassThroughData>::plan_exits(
+            world_position,
+            &mut self.game_context.random.prht,
+            UVec2::new(GRID_WIDTH, GRID_HEIGHT),
+            true,
+        )
+    }
+
+    fn is_loaded(&mut self, world_position: WorldPosition) -> bool { self.is_map_loaded(world_position) }
+}
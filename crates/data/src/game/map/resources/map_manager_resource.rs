@@ -6,8 +6,24 @@ pub struct MapManagerResource {
     pub current_map: (WorldPosition, Map),
     pub loaded_maps: HashMap<WorldPosition, Map>,
     pub visible_tiles: VisibilityMap,
+    pub terrain_changes: Vec<Position>,
+    pub requested_maps: HashSet<WorldPosition>,
+    pub newly_loaded_maps: Vec<WorldPosition>,
     pub terrain_layer: Entity,
     pub features_layer: Entity,
+    pub neighbour_layers: Vec<NeighbourLayers>,
+}
+
+/// Tilemaps drawing one of the maps surrounding the current map.
+pub struct NeighbourLayers {
+    /// Offset in maps from the current map.
+    pub offset: IVec2,
+    pub terrain_layer: Entity,
+    pub features_layer: Entity,
+    /// The map last drawn on these layers.
+    pub shown: Option<WorldPosition>,
+    /// Were any of the tiles visible last time they were drawn?
+    pub was_visible: bool,
 }
 
 // Constructor
@@ -17,13 +33,19 @@ impl MapManagerResource {
         map: Map,
         terrain_layer: Entity,
         features_layer: Entity,
+        neighbour_layers: Vec<NeighbourLayers>,
     ) -> Self {
+        let visible_tiles = VisibilityMap::new(map.size);
         Self {
             current_map: (world_position, map),
             loaded_maps: HashMap::new(),
-            visible_tiles: VisibilityMap::new(),
+            visible_tiles,
+            terrain_changes: Vec::new(),
+            requested_maps: HashSet::new(),
+            newly_loaded_maps: Vec::new(),
             terrain_layer,
             features_layer,
+            neighbour_layers,
         }
     }
 }
@@ -67,6 +67,25 @@ impl TerrainType {
             Self::Water => 1.45,
         }
     }
+
+    /// Tile drawn on the terrain layer underneath everything else.
+    /// Walls and water are auto-tiled on the features layer, so they sit on floor here.
+    pub const fn terrain_tile_id(&self) -> usize {
+        match self {
+            Self::None => TILE_TERRAIN_MISSING_ID,
+            Self::Floor | Self::Wall | Self::Water => TILE_TERRAIN_FLOOR_ID,
+        }
+    }
+
+    /// First tile of the auto-tile set drawn on the features layer
+    /// `None` if this terrain isn't auto-tiled
+    pub const fn auto_tile_start_id(&self) -> Option<usize> {
+        match self {
+            Self::None | Self::Floor => None,
+            Self::Wall => Some(TILE_FEATURES_WALL_AUTO_START_ID),
+            Self::Water => Some(TILE_FEATURES_WATER_AUTO_START_ID),
+        }
+    }
 }
 
 impl From<TerrainType> for u32 {
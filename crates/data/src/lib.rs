@@ -4,8 +4,12 @@
 #![allow(clippy::module_inception)]
 
 mod actions {
-    mod action_type;
-    pub use action_type::*;
+    mod action;
+    pub use action::*;
+    mod action_failure;
+    pub use action_failure::*;
+    mod interrupts;
+    pub use interrupts::*;
 }
 
 mod actors {
@@ -60,6 +64,12 @@ mod components {
     }
     pub use bundles::*;
 
+    mod facing {
+        mod facing;
+        pub use facing::*;
+    }
+    pub use facing::*;
+
     mod movement {
         mod movement;
         pub use movement::*;
@@ -88,12 +98,24 @@ mod components {
     pub use equipable::*;
     mod field_of_view;
     pub use field_of_view::*;
+    mod ghost;
+    pub use ghost::*;
     mod health;
     pub use health::*;
+    mod light_source;
+    pub use light_source::*;
+    mod path_cache;
+    pub use path_cache::*;
+    mod persistent_id;
+    pub use persistent_id::*;
+    mod speed;
+    pub use speed::*;
     mod tags;
     pub use tags::*;
     mod target_visualizer;
     pub use target_visualizer::*;
+    mod viewshed;
+    pub use viewshed::*;
 }
 
 mod game {
@@ -107,6 +129,8 @@ mod game {
 
     mod map {
         mod functions {
+            mod auto_tile;
+            pub use auto_tile::*;
             mod create_tilemap;
             pub use create_tilemap::*;
         }
@@ -130,6 +154,8 @@ mod game {
         }
         pub use tiles::*;
 
+        mod entity_memory;
+        pub use entity_memory::*;
         mod map;
         pub use map::*;
         mod map_layer;
@@ -151,17 +177,29 @@ mod map_gen {
     mod builders {
         mod cellular_automata_builder;
         pub use cellular_automata_builder::*;
+        mod exit_builder;
+        pub use exit_builder::*;
         mod finalizer_builder;
         pub use finalizer_builder::*;
+        mod maze_builder;
+        pub use maze_builder::*;
         mod scatter_builder;
         pub use scatter_builder::*;
         mod set_builder;
         pub use set_builder::*;
+        mod voronoi_builder;
+        pub use voronoi_builder::*;
+        mod wave_function_collapse_builder;
+        pub use wave_function_collapse_builder::*;
+        mod wave_function_collapse_sample;
+        pub use wave_function_collapse_sample::*;
     }
     pub use builders::*;
 
     mod map_architect;
     pub use map_architect::*;
+    mod map_exit;
+    pub use map_exit::*;
     mod map_gen_data;
     pub use map_gen_data::*;
     mod map_generator;
@@ -169,6 +207,32 @@ mod map_gen {
 }
 
 pub mod fov {
+    mod diamond_walls {
+        mod diamond_walls;
+        pub use diamond_walls::*;
+    }
+    pub(crate) use diamond_walls::*;
+
+    mod line_of_sight {
+        mod line_of_sight;
+        pub use line_of_sight::*;
+    }
+    pub use line_of_sight::*;
+
+    mod permissive {
+        mod precise_permissive;
+        pub use precise_permissive::*;
+        mod sight_line;
+        mod view;
+    }
+    pub(crate) use permissive::*;
+
+    mod raycast {
+        mod raycast;
+        pub use raycast::*;
+    }
+    pub(crate) use raycast::*;
+
     mod shadowcast {
         mod shadowcast;
         pub use shadowcast::*;
@@ -185,6 +249,8 @@ pub mod fov {
     }
     pub(crate) use shared::*;
 
+    mod cone_receiver;
+    pub use cone_receiver::*;
     mod fov;
     pub use fov::*;
     mod fov_provider;
@@ -193,13 +259,20 @@ pub mod fov {
     pub use fov_receiver::*;
     mod visibility_map;
     pub use visibility_map::*;
+    #[cfg(test)]
+    mod test_walls;
+    #[cfg(test)]
+    pub(crate) use test_walls::*;
 }
 
 mod pathfinding {
     mod astar {
         mod astar;
         pub use astar::*;
+        #[cfg(test)]
         mod astar_node;
+        #[cfg(test)]
+        mod index_list_astar;
     }
     pub use astar::*;
 
@@ -209,6 +282,26 @@ mod pathfinding {
     }
     pub use dijkstra::*;
 
+    mod dijkstra_map {
+        mod dijkstra_map;
+        pub use dijkstra_map::*;
+    }
+    pub use dijkstra_map::*;
+
+    mod hierarchical {
+        mod hierarchical;
+        pub use hierarchical::*;
+        mod map_graph_provider;
+        pub use map_graph_provider::*;
+    }
+    pub use hierarchical::*;
+
+    mod jump_point {
+        mod jump_point;
+        pub use jump_point::*;
+    }
+    pub use jump_point::*;
+
     mod shared {
         mod path_algorithm;
         pub use path_algorithm::*;
@@ -225,6 +318,14 @@ mod resources {
     pub use action_queue::*;
     mod app_settings;
     pub use app_settings::*;
+    mod chase_maps;
+    pub use chase_maps::*;
+    mod cursor_position;
+    pub use cursor_position::*;
+    mod message_log;
+    pub use message_log::*;
+    mod persistent_ids;
+    pub use persistent_ids::*;
     mod player_entity;
     pub use player_entity::*;
     mod tile_ids;
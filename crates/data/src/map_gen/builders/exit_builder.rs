@@ -0,0 +1,206 @@
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+/// Places an exit on every edge of the map and a pair of stairs.
+///
+/// Exit positions are hashed from both maps sharing the edge (or staircase)
+/// using the world's `Prht`, so two maps generated independently agree on
+/// where their openings line up. Exits are recorded in `MapGenData::exit_positions`,
+/// and a path of `floor_value` is carved from each exit until it meets open floor.
+///
+/// It can run after any builder, the carved paths go through whatever is already there.
+pub struct ExitBuilder<T> {
+    world_position: WorldPosition,
+    world_prht: Prht,
+    floor_value: u32,
+    stairs: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T> ExitBuilder<T> {
+    /// `world_prht` must be the same `Prht` for every map in the world,
+    /// not the map's own `Random`.
+    pub fn new(world_position: WorldPosition, world_prht: Prht) -> Box<Self> {
+        Box::new(Self {
+            world_position,
+            world_prht,
+            floor_value: TerrainType::Floor.into(),
+            stairs: true,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn with_floor_value(mut self, floor_value: u32) -> Box<Self> {
+        self.floor_value = floor_value;
+        Box::new(self)
+    }
+
+    pub fn without_stairs(mut self) -> Box<Self> {
+        self.stairs = false;
+        Box::new(self)
+    }
+
+    /// Hash shared by both maps on either side of an exit.
+    /// The pair is ordered so it doesn't matter which side asks.
+    fn shared_hash(world_prht: &mut Prht, a: WorldPosition, b: WorldPosition) -> u64 {
+        let (low, high) = if a.xyz().to_array() <= b.xyz().to_array() { (a, b) } else { (b, a) };
+        let pack = |low: i32, high: i32| ((low as i64) << 32) | (high as u32 as i64);
+        world_prht.get(
+            pack(low.x(), high.x()),
+            pack(low.y(), high.y()),
+            pack(low.z(), high.z()),
+        )
+    }
+
+    fn edge_position(size: UVec2, direction: CardinalDirection, hash: u64) -> UVec2 {
+        // Stay off the corners.
+        let along_x = 1 + (hash % (size.x - 2) as u64) as u32;
+        let along_y = 1 + (hash % (size.y - 2) as u64) as u32;
+        match direction {
+            CardinalDirection::North => UVec2::new(along_x, size.y - 1),
+            CardinalDirection::South => UVec2::new(along_x, 0),
+            CardinalDirection::East => UVec2::new(size.x - 1, along_y),
+            CardinalDirection::West => UVec2::new(0, along_y),
+        }
+    }
+
+    /// Staircases between `lower_z` and the level above it use only columns of the same
+    /// parity as `lower_z`, so a map's up and down stairs can never share a tile and
+    /// both maps of a pair agree on the position without knowing about the other stairs.
+    fn stairs_position(size: UVec2, hash: u64, lower_z: i32) -> UVec2 {
+        let parity = lower_z.rem_euclid(2) as u32;
+        let columns = (size.x - 1 - parity) / 2;
+        // A map 3 wide only has a single column to offer.
+        let x = if columns == 0 { 1 } else { 1 + parity + 2 * (hash % columns as u64) as u32 };
+        UVec2::new(x, 1 + ((hash >> 32) % (size.y - 2) as u64) as u32)
+    }
+
+    /// Carves from `start` towards the middle of the map until reaching existing floor.
+    fn carve_to_floor(&self, data: &mut MapGenData<T>, start: UVec2) {
+        let center = (data.size / 2).as_ivec2();
+        let mut current = start.as_ivec2();
+        loop {
+            match data.terrain_grid.get(current) {
+                Some(&value) if value == self.floor_value && current != start.as_ivec2() => return,
+                Some(_) => {
+                    data.terrain_grid.set(current, self.floor_value);
+                },
+                None => return,
+            }
+
+            if current == center {
+                return;
+            }
+            current += (center - current).signum();
+        }
+    }
+
+    /// Where the exits of the map at `world_position` will be, without generating it.
+    /// Every map generated with the same `world_prht` and `size` agrees with this.
+    pub fn plan_exits(
+        world_position: WorldPosition,
+        world_prht: &mut Prht,
+        size: UVec2,
+        stairs: bool,
+    ) -> Vec<MapExit> {
+        let exit = |position, exit_type: ExitType| MapExit {
+            position,
+            exit_type,
+            destination: exit_type.destination(world_position),
+        };
+
+        let mut exits = Vec::new();
+        for direction in CardinalDirection::all() {
+            let exit_type = ExitType::Edge(direction);
+            let neighbour = exit_type.destination(world_position);
+            let hash = Self::shared_hash(world_prht, world_position, neighbour);
+            exits.push(exit(Self::edge_position(size, direction, hash), exit_type));
+        }
+
+        if stairs {
+            for exit_type in [ExitType::StairsUp, ExitType::StairsDown] {
+                let destination = exit_type.destination(world_position);
+                let hash = Self::shared_hash(world_prht, world_position, destination);
+                let lower_z = world_position.z().min(destination.z());
+                exits.push(exit(Self::stairs_position(size, hash, lower_z), exit_type));
+            }
+        }
+
+        exits
+    }
+}
+
+impl<T> MapArchitect<T> for ExitBuilder<T> {
+    fn generate(&mut self, data: &mut MapGenData<T>) {
+        if data.size.x < 3 || data.size.y < 3 {
+            error!(
+                "ExitBuilder Grid({}, {}) is too small to place exits",
+                data.size.x, data.size.y
+            );
+            return;
+        }
+
+        for exit in Self::plan_exits(
+            self.world_position,
+            &mut self.world_prht,
+            data.size,
+            self.stairs,
+        ) {
+            self.carve_to_floor(data, exit.position);
+            data.exit_positions.push(exit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: UVec2 = UVec2::new(80, 45);
+
+    fn exit_position(exits: &[MapExit], exit_type: ExitType) -> UVec2 {
+        exits.iter().find(|exit| exit.exit_type == exit_type).map(|exit| exit.position).unwrap()
+    }
+
+    fn plan(world_position: WorldPosition) -> Vec<MapExit> {
+        ExitBuilder::<u32>::plan_exits(world_position, &mut Prht::new(1234), SIZE, true)
+    }
+
+    #[test]
+    fn stairs_line_up_across_levels() {
+        for x in -2..=2 {
+            for z in -8..=8 {
+                let exits = plan(WorldPosition::new(x, 0, z));
+                let below = plan(WorldPosition::new(x, 0, z - 1));
+                assert_eq!(
+                    exit_position(&exits, ExitType::StairsDown),
+                    exit_position(&below, ExitType::StairsUp)
+                );
+                assert_ne!(
+                    exit_position(&exits, ExitType::StairsDown),
+                    exit_position(&exits, ExitType::StairsUp)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn opposite_edges_line_up() {
+        for x in -3..=3 {
+            for y in -3..=3 {
+                let exits = plan(WorldPosition::new(x, y, 0));
+                let east = plan(WorldPosition::new(x + 1, y, 0));
+                let north = plan(WorldPosition::new(x, y + 1, 0));
+
+                let east_exit = exit_position(&exits, ExitType::Edge(CardinalDirection::East));
+                let west_exit = exit_position(&east, ExitType::Edge(CardinalDirection::West));
+                assert_eq!(east_exit.y, west_exit.y);
+
+                let north_exit = exit_position(&exits, ExitType::Edge(CardinalDirection::North));
+                let south_exit = exit_position(&north, ExitType::Edge(CardinalDirection::South));
+                assert_eq!(north_exit.x, south_exit.x);
+            }
+        }
+    }
+}
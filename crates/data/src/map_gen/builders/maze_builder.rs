@@ -0,0 +1,354 @@
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+const DEFAULT_CORRIDOR_WIDTH: u32 = 1;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MazeAlgorithm {
+    /// Long winding corridors with few branches.
+    #[default]
+    RecursiveBacktracker,
+    /// Lots of short branches and dead ends.
+    Prims,
+}
+
+/// Generates a maze inside the rectangle.
+///
+/// Writes `0` for corridors and `1` for walls, so follow up with
+/// `FinalizerBuilder::new(..).with_input_values(0, 1)`.
+pub struct MazeBuilder<T> {
+    rect: Option<Rectangle>,
+    algorithm: MazeAlgorithm,
+    corridor_width: u32,
+    braid_percent: u32,
+    phantom: PhantomData<T>,
+}
+
+impl<T> MazeBuilder<T> {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            rect: None,
+            algorithm: MazeAlgorithm::default(),
+            corridor_width: DEFAULT_CORRIDOR_WIDTH,
+            braid_percent: 0,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn with_rect(mut self, rectangle: Rectangle) -> Box<Self> {
+        self.rect = Some(rectangle);
+        Box::new(self)
+    }
+
+    pub fn with_algorithm(mut self, algorithm: MazeAlgorithm) -> Box<Self> {
+        self.algorithm = algorithm;
+        Box::new(self)
+    }
+
+    /// Width of the corridors in tiles, walls are always 1 tile thick.
+    pub fn with_corridor_width(mut self, corridor_width: u32) -> Box<Self> {
+        self.corridor_width = corridor_width.max(1);
+        Box::new(self)
+    }
+
+    /// Chance (`0..=100`) for each dead end to be knocked through into a neighbour.
+    /// `0` leaves a perfect maze, `100` removes every dead end.
+    pub fn with_braid(mut self, braid_percent: u32) -> Box<Self> {
+        self.braid_percent = braid_percent.min(100);
+        Box::new(self)
+    }
+
+    /// Opens a passage between a cell and its neighbour in `direction`.
+    /// Each cell holds a raw `DirectionBitmap` of its open sides.
+    fn connect(passages: &mut Grid<u8>, cell: IVec2, direction: CardinalDirection) {
+        if let Some(bitmap) = passages.get_mut(cell) {
+            *bitmap |= direction.direction().bitmap_raw();
+        }
+        if let Some(bitmap) = passages.get_mut(cell + direction.coord()) {
+            *bitmap |= direction.opposite().direction().bitmap_raw();
+        }
+    }
+
+    fn recursive_backtracker(passages: &mut Grid<u8>, prng: &mut Prng) {
+        let mut visited = BitGrid::new_default(passages.size());
+        let start = IVec2::new(
+            prng.max(passages.width()) as i32,
+            prng.max(passages.height()) as i32,
+        );
+        visited.set(start, true);
+
+        let mut stack = vec![start];
+        while let Some(&cell) = stack.last() {
+            let unvisited: Vec<CardinalDirection> = CardinalDirection::all()
+                .filter(|direction| visited.get(cell + direction.coord()).map_or(false, |visited| !*visited))
+                .collect();
+
+            match prng.choose(&unvisited) {
+                Some(&direction) => {
+                    let next = cell + direction.coord();
+                    Self::connect(passages, cell, direction);
+                    visited.set(next, true);
+                    stack.push(next);
+                },
+                None => {
+                    stack.pop();
+                },
+            }
+        }
+    }
+
+    fn prims(passages: &mut Grid<u8>, prng: &mut Prng) {
+        let mut visited = BitGrid::new_default(passages.size());
+        let start = IVec2::new(
+            prng.max(passages.width()) as i32,
+            prng.max(passages.height()) as i32,
+        );
+        visited.set(start, true);
+
+        let mut frontier: Vec<(IVec2, CardinalDirection)> =
+            CardinalDirection::all().map(|direction| (start, direction)).collect();
+        while !frontier.is_empty() {
+            let (cell, direction) = frontier.swap_remove(prng.max(frontier.len() as u32) as usize);
+            let next = cell + direction.coord();
+            match visited.get(next) {
+                Some(&false) => {},
+                _ => continue,
+            }
+
+            Self::connect(passages, cell, direction);
+            visited.set(next, true);
+            frontier.extend(CardinalDirection::all().map(|direction| (next, direction)));
+        }
+    }
+
+    /// Knocks dead ends through into a neighbouring cell, preferring other dead ends.
+    fn braid(passages: &mut Grid<u8>, braid_percent: u32, prng: &mut Prng) {
+        for y in 0..passages.height() as i32 {
+            for x in 0..passages.width() as i32 {
+                let cell = IVec2::new(x, y);
+                if !Self::is_dead_end(passages, cell) || prng.max(100) >= braid_percent {
+                    continue;
+                }
+
+                let closed: Vec<CardinalDirection> = CardinalDirection::all()
+                    .filter(|direction| {
+                        passages.in_bounds(cell + direction.coord()) &&
+                            !DirectionBitmap::new(*passages.get_unchecked(cell))
+                                .contains(direction.direction())
+                    })
+                    .collect();
+                let dead_ends: Vec<CardinalDirection> = closed
+                    .iter()
+                    .copied()
+                    .filter(|direction| Self::is_dead_end(passages, cell + direction.coord()))
+                    .collect();
+
+                let choices = if dead_ends.is_empty() { &closed } else { &dead_ends };
+                if let Some(&direction) = prng.choose(choices) {
+                    Self::connect(passages, cell, direction);
+                }
+            }
+        }
+    }
+
+    fn is_dead_end(passages: &Grid<u8>, cell: IVec2) -> bool {
+        passages.get(cell).map_or(false, |bitmap| bitmap.count_ones() == 1)
+    }
+}
+
+impl<T> MapArchitect<T> for MazeBuilder<T> {
+    fn generate(&mut self, data: &mut MapGenData<T>) {
+        let rect = match &self.rect {
+            Some(r) => *r,
+            None => Rectangle::new((0i32, 0), data.size - UVec2::new(1, 1)),
+        };
+
+        if !data.terrain_grid.in_bounds(rect.min()) || !data.terrain_grid.in_bounds(rect.max()) {
+            error!(
+                "MazeBuilder Rectangle{{ {}, {} }} is outside of bounds for Grid({}, {})",
+                rect.min(),
+                rect.max(),
+                data.terrain_grid.width(),
+                data.terrain_grid.height()
+            );
+            return;
+        }
+
+        // Each cell is a corridor plus the wall on its far side,
+        // with one extra wall along the near edge of the rectangle.
+        let step = self.corridor_width as i32 + 1;
+        let cells = UVec2::new((rect.width() / step) as u32, (rect.height() / step) as u32);
+        if cells.x == 0 || cells.y == 0 {
+            error!(
+                "MazeBuilder Rectangle{{ {}, {} }} is too small for a corridor width of {}",
+                rect.min(),
+                rect.max(),
+                self.corridor_width
+            );
+            return;
+        }
+
+        let mut passages = Grid::new_default(cells);
+        match self.algorithm {
+            MazeAlgorithm::RecursiveBacktracker => {
+                Self::recursive_backtracker(&mut passages, &mut data.random.prng)
+            },
+            MazeAlgorithm::Prims => Self::prims(&mut passages, &mut data.random.prng),
+        }
+
+        if self.braid_percent > 0 {
+            Self::braid(&mut passages, self.braid_percent, &mut data.random.prng);
+        }
+
+        rect.for_each(|v| {
+            data.terrain_grid.set(v, 1);
+        });
+
+        let width = self.corridor_width as i32;
+        for y in 0..cells.y as i32 {
+            for x in 0..cells.x as i32 {
+                let cell = IVec2::new(x, y);
+                let origin = rect.min() + IVec2::ONE + cell * step;
+                let bitmap = DirectionBitmap::new(*passages.get_unchecked(cell));
+
+                // Open the cell, plus the wall to the north / east if there's a passage.
+                // South / West are opened by the neighbouring cell.
+                let east = i32::from(bitmap.contains(GridDirection::East));
+                let north = i32::from(bitmap.contains(GridDirection::North));
+                Rectangle::new(origin, origin + IVec2::new(width - 1 + east, width - 1)).for_each(|v| {
+                    data.terrain_grid.set(v, 0);
+                });
+                Rectangle::new(origin, origin + IVec2::new(width - 1, width - 1 + north)).for_each(|v| {
+                    data.terrain_grid.set(v, 0);
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    const SIZE: UVec2 = UVec2::new(40, 30);
+
+    /// Runs `builder` over a grid of `7`s, so anything it didn't touch stands out.
+    fn generate(seed: u64, mut builder: Box<MazeBuilder<()>>) -> Grid<u32> {
+        let mut data = MapGenData::new(SIZE, Random::new(seed), ());
+        data.terrain_grid = Grid::new_copy(SIZE, 7);
+        builder.generate(&mut data);
+        data.terrain_grid
+    }
+
+    fn floor_tiles(terrain: &Grid<u32>) -> Vec<IVec2> {
+        RectIter::new((0i32, 0), SIZE.as_ivec2() - IVec2::ONE)
+            .filter(|&point| terrain.get(point) == Some(&0))
+            .collect()
+    }
+
+    fn floor_neighbours(terrain: &Grid<u32>, point: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        CardinalDirection::all()
+            .map(move |direction| point + direction.coord())
+            .filter(|&neighbour| terrain.get(neighbour) == Some(&0))
+    }
+
+    fn reachable(terrain: &Grid<u32>, start: IVec2) -> usize {
+        let mut seen = HashSet::new();
+        seen.insert(start);
+        let mut queue = VecDeque::from([start]);
+        while let Some(point) = queue.pop_front() {
+            for neighbour in floor_neighbours(terrain, point) {
+                if seen.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    #[test]
+    fn perfect_mazes_are_trees() {
+        for algorithm in [MazeAlgorithm::RecursiveBacktracker, MazeAlgorithm::Prims] {
+            for seed in 0..8 {
+                let terrain = generate(seed, MazeBuilder::new().with_algorithm(algorithm));
+                let floor = floor_tiles(&terrain);
+                let edges: usize =
+                    floor.iter().map(|&point| floor_neighbours(&terrain, point).count()).sum::<usize>() / 2;
+
+                assert_eq!(
+                    reachable(&terrain, floor[0]),
+                    floor.len(),
+                    "{:?} seed {}",
+                    algorithm,
+                    seed
+                );
+                assert_eq!(edges + 1, floor.len(), "{:?} seed {}", algorithm, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn braiding_removes_dead_ends() {
+        for algorithm in [MazeAlgorithm::RecursiveBacktracker, MazeAlgorithm::Prims] {
+            for seed in 0..8 {
+                let terrain = generate(
+                    seed,
+                    MazeBuilder::new().with_algorithm(algorithm).with_braid(100),
+                );
+                for point in floor_tiles(&terrain) {
+                    assert!(
+                        floor_neighbours(&terrain, point).count() > 1,
+                        "{:?} seed {} has a dead end at {}",
+                        algorithm,
+                        seed,
+                        point
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stays_in_the_rect_with_wide_corridors() {
+        let rect = Rectangle::new((5i32, 3), (30, 20));
+        for corridor_width in 1..=3 {
+            let terrain = generate(
+                0,
+                MazeBuilder::new().with_rect(rect).with_corridor_width(corridor_width),
+            );
+            for point in RectIter::new((0i32, 0), SIZE.as_ivec2() - IVec2::ONE) {
+                let inside = point.cmpge(rect.min()).all() && point.cmple(rect.max()).all();
+                let border = point.x == rect.min().x ||
+                    point.x == rect.max().x ||
+                    point.y == rect.min().y ||
+                    point.y == rect.max().y;
+                let value = *terrain.get_unchecked(point);
+                match (inside, border) {
+                    (false, _) => assert_eq!(value, 7, "{} is outside the rect", point),
+                    (true, true) => assert_eq!(value, 1, "{} is on the border", point),
+                    _ => {},
+                }
+            }
+
+            // Every floor tile is in a corridor `corridor_width` wide, and none are wider.
+            let is_floor_square = |min: IVec2, width: u32| {
+                RectIter::new(min, min + IVec2::splat(width as i32 - 1))
+                    .all(|point| terrain.get(point) == Some(&0))
+            };
+            for point in floor_tiles(&terrain) {
+                let width = corridor_width as i32;
+                assert!(
+                    RectIter::new(point - IVec2::splat(width - 1), point)
+                        .any(|min| is_floor_square(min, corridor_width)),
+                    "{} is in a corridor narrower than {}",
+                    point,
+                    corridor_width
+                );
+                assert!(!is_floor_square(point, corridor_width + 1));
+            }
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+const DEFAULT_SEED_COUNT: u32 = 8;
+
+/// Splits the rectangle into regions around randomly scattered seeds.
+///
+/// Each cell belongs to its nearest seed and region ids are written to
+/// `MapGenData::region_grid`, numbered after the highest id already there
+/// so several `VoronoiBuilder`s can share the grid.
+/// `with_border_walls` also writes `1` (wall) to `terrain_grid` along the borders
+/// and `0` (floor) everywhere else.
+pub struct VoronoiBuilder<T> {
+    rect: Option<Rectangle>,
+    seed_count: u32,
+    distance: DistanceAlg,
+    border_walls: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T> VoronoiBuilder<T> {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            rect: None,
+            seed_count: DEFAULT_SEED_COUNT,
+            distance: DistanceAlg::Pythagoras,
+            border_walls: false,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn with_rect(mut self, rectangle: Rectangle) -> Box<Self> {
+        self.rect = Some(rectangle);
+        Box::new(self)
+    }
+
+    pub fn with_seeds(mut self, seed_count: u32) -> Box<Self> {
+        self.seed_count = seed_count.max(1);
+        Box::new(self)
+    }
+
+    pub fn with_distance(mut self, distance: DistanceAlg) -> Box<Self> {
+        self.distance = distance;
+        Box::new(self)
+    }
+
+    pub fn with_border_walls(mut self) -> Box<Self> {
+        self.border_walls = true;
+        Box::new(self)
+    }
+
+    fn scatter_seeds(seed_count: u32, rect: &Rectangle, prng: &mut Prng) -> Vec<IVec2> {
+        (0..seed_count)
+            .map(|_| {
+                IVec2::new(
+                    prng.range(rect.min().x as u32..=rect.max().x as u32) as i32,
+                    prng.range(rect.min().y as u32..=rect.max().y as u32) as i32,
+                )
+            })
+            .collect()
+    }
+
+    /// A cell is on the border if any of its neighbours belongs to a region with a lower id.
+    /// Only one side of each border is marked, which keeps the lines 1 tile thick
+    /// while still closing diagonal gaps.
+    fn is_border(region_grid: &Grid<u32>, rect: &Rectangle, point: IVec2) -> bool {
+        let region = *region_grid.get_unchecked(point);
+        GridDirection::all().any(|direction| {
+            let neighbour = point + direction.coord();
+            neighbour.cmpge(rect.min()).all() &&
+                neighbour.cmple(rect.max()).all() &&
+                *region_grid.get_unchecked(neighbour) < region
+        })
+    }
+}
+
+impl<T> MapArchitect<T> for VoronoiBuilder<T> {
+    fn generate(&mut self, data: &mut MapGenData<T>) {
+        let rect = match &self.rect {
+            Some(r) => *r,
+            None => Rectangle::new((0i32, 0), data.size - UVec2::new(1, 1)),
+        };
+
+        if !data.terrain_grid.in_bounds(rect.min()) || !data.terrain_grid.in_bounds(rect.max()) {
+            error!(
+                "VoronoiBuilder Rectangle{{ {}, {} }} is outside of bounds for Grid({}, {})",
+                rect.min(),
+                rect.max(),
+                data.terrain_grid.width(),
+                data.terrain_grid.height()
+            );
+            return;
+        }
+
+        let seeds = Self::scatter_seeds(self.seed_count, &rect, &mut data.random.prng);
+
+        let first_region = data.region_grid.cells.iter().copied().max().unwrap_or_default() + 1;
+        rect.for_each(|v| {
+            let mut nearest = 0;
+            let mut nearest_distance = f32::MAX;
+            for (index, &seed) in seeds.iter().enumerate() {
+                let distance = self.distance.distance2d(v, seed);
+                if distance < nearest_distance {
+                    nearest = index;
+                    nearest_distance = distance;
+                }
+            }
+            data.region_grid.set(v, first_region + nearest as u32);
+        });
+
+        if self.border_walls {
+            rect.for_each(|v| {
+                let value = u32::from(Self::is_border(&data.region_grid, &rect, v));
+                data.terrain_grid.set(v, value);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: UVec2 = UVec2::new(40, 30);
+
+    fn generate(seed: u64, mut builder: Box<VoronoiBuilder<()>>) -> MapGenData<()> {
+        let mut data = MapGenData::new(SIZE, Random::new(seed), ());
+        builder.generate(&mut data);
+        data
+    }
+
+    fn neighbours(point: IVec2) -> impl Iterator<Item = IVec2> {
+        GridDirection::all()
+            .map(move |direction| point + direction.coord())
+            .filter(|neighbour| neighbour.cmpge(IVec2::ZERO).all() && neighbour.cmplt(SIZE.as_ivec2()).all())
+    }
+
+    #[test]
+    fn cells_belong_to_the_nearest_seed() {
+        let rect = Rectangle::new((0i32, 0), SIZE.as_ivec2() - IVec2::ONE);
+        for distance in [
+            DistanceAlg::Pythagoras,
+            DistanceAlg::Manhattan,
+            DistanceAlg::Chebyshev,
+            DistanceAlg::Diagonal,
+        ] {
+            for seed in 0..4 {
+                let data = generate(seed, VoronoiBuilder::new().with_distance(distance));
+                // The seeds are the first thing drawn from the generator.
+                let seeds = VoronoiBuilder::<()>::scatter_seeds(
+                    DEFAULT_SEED_COUNT,
+                    &rect,
+                    &mut Random::new(seed).prng,
+                );
+
+                rect.for_each(|point| {
+                    // Regions are numbered from 1 on an empty grid.
+                    let region = *data.region_grid.get_unchecked(point);
+                    let assigned = distance.distance2d(point, seeds[region as usize - 1]);
+                    let nearest =
+                        seeds.iter().map(|&other| distance.distance2d(point, other)).fold(f32::MAX, f32::min);
+                    assert_eq!(
+                        assigned, nearest,
+                        "{:?} seed {} at {}",
+                        distance, seed, point
+                    );
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_regions() {
+        for seed in 0..4 {
+            let first = generate(
+                seed,
+                VoronoiBuilder::new().with_seeds(12).with_border_walls(),
+            );
+            let second = generate(
+                seed,
+                VoronoiBuilder::new().with_seeds(12).with_border_walls(),
+            );
+            assert_eq!(first.region_grid, second.region_grid);
+            assert_eq!(first.terrain_grid, second.terrain_grid);
+        }
+    }
+
+    #[test]
+    fn border_walls_separate_every_region() {
+        let rect = Rectangle::new((0i32, 0), SIZE.as_ivec2() - IVec2::ONE);
+        for seed in 0..4 {
+            let data = generate(
+                seed,
+                VoronoiBuilder::new().with_seeds(12).with_border_walls(),
+            );
+            let is_floor = |point: IVec2| *data.terrain_grid.get_unchecked(point) == 0;
+            let region = |point: IVec2| *data.region_grid.get_unchecked(point);
+
+            rect.for_each(|point| {
+                if !is_floor(point) {
+                    return;
+                }
+                for neighbour in neighbours(point).filter(|&neighbour| is_floor(neighbour)) {
+                    assert_eq!(
+                        region(point),
+                        region(neighbour),
+                        "seed {} at {} and {}",
+                        seed,
+                        point,
+                        neighbour
+                    );
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn border_walls_are_one_tile_thick() {
+        let rect = Rectangle::new((0i32, 0), SIZE.as_ivec2() - IVec2::ONE);
+        // With only two regions there are no junctions, so every wall has floor on both sides,
+        // unless the map edge cuts it off.
+        for seed in 0..8 {
+            let data = generate(
+                seed,
+                VoronoiBuilder::new().with_seeds(2).with_border_walls(),
+            );
+            let is_floor = |point: IVec2| *data.terrain_grid.get_unchecked(point) == 0;
+            let region = |point: IVec2| *data.region_grid.get_unchecked(point);
+
+            rect.for_each(|point| {
+                if is_floor(point) {
+                    return;
+                }
+                let floor: HashSet<u32> =
+                    neighbours(point).filter(|&neighbour| is_floor(neighbour)).map(region).collect();
+                let on_edge = point.cmpeq(rect.min()).any() || point.cmpeq(rect.max()).any();
+                assert!(floor.contains(&1), "seed {} at {}", seed, point);
+                assert!(on_edge || floor.contains(&2), "seed {} at {}", seed, point);
+            });
+        }
+    }
+}
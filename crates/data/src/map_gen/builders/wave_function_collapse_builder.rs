@@ -0,0 +1,386 @@
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+const DEFAULT_MAX_RETRIES: u32 = 10;
+
+/// Synthesizes `terrain_grid` content from small sample maps.
+///
+/// Every value that appears in the samples becomes a tile, and two tiles
+/// may only touch on a side if they touched on that side somewhere in a sample.
+/// Samples wrap around at their edges when learning, so border tiles get rules too.
+/// The values are written to the map exactly as they appear in the samples
+/// (`0` floor / `1` wall for ASCII samples), so follow up with a `FinalizerBuilder`.
+pub struct WaveFunctionCollapseBuilder<T> {
+    rect: Option<Rectangle>,
+    samples: Vec<WaveFunctionCollapseSample>,
+    max_retries: u32,
+    phantom: PhantomData<T>,
+}
+
+impl<T> WaveFunctionCollapseBuilder<T> {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            rect: None,
+            samples: Vec::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn with_rect(mut self, rectangle: Rectangle) -> Box<Self> {
+        self.rect = Some(rectangle);
+        Box::new(self)
+    }
+
+    pub fn with_sample(mut self, sample: WaveFunctionCollapseSample) -> Box<Self> {
+        self.samples.push(sample);
+        Box::new(self)
+    }
+
+    /// How many times to start over after running into a contradiction
+    pub fn with_max_retries(mut self, max_retries: u32) -> Box<Self> {
+        self.max_retries = max_retries;
+        Box::new(self)
+    }
+
+    /// Starts over up to `max_retries` times whenever an attempt runs into a contradiction.
+    fn collapse_with_retries(
+        rules: &AdjacencyRules,
+        size: UVec2,
+        prng: &mut Prng,
+        max_retries: u32,
+    ) -> Option<Vec<usize>> {
+        for attempt in 0..=max_retries {
+            if let Some(tiles) = Self::collapse(rules, size, prng) {
+                return Some(tiles);
+            }
+
+            info!(
+                "WaveFunctionCollapseBuilder hit a contradiction on attempt {}/{}",
+                attempt + 1,
+                max_retries + 1
+            );
+        }
+
+        None
+    }
+
+    /// Runs a single attempt, returning the chosen tile for every cell
+    /// or `None` if a cell ran out of options.
+    fn collapse(rules: &AdjacencyRules, size: UVec2, prng: &mut Prng) -> Option<Vec<usize>> {
+        let tile_count = rules.values.len();
+        let cell_count = (size.x * size.y) as usize;
+
+        let mut wave = vec![vec![true; tile_count]; cell_count];
+        let mut options = vec![tile_count; cell_count];
+        let mut stack = Vec::new();
+
+        loop {
+            // Find the undecided cell with the fewest options, breaking ties randomly.
+            let mut next = None;
+            let mut fewest = usize::MAX;
+            let mut ties = 0;
+            for (index, &count) in options.iter().enumerate() {
+                if count <= 1 {
+                    continue;
+                }
+                if count < fewest {
+                    fewest = count;
+                    next = Some(index);
+                    ties = 1;
+                } else if count == fewest {
+                    ties += 1;
+                    if prng.max(ties) == 0 {
+                        next = Some(index);
+                    }
+                }
+            }
+
+            // Everything is decided.
+            let Some(index) = next else { break; };
+
+            // Pick one of the remaining tiles weighted by how often it appears in the samples.
+            let total: u32 =
+                (0..tile_count).filter(|&tile| wave[index][tile]).map(|tile| rules.weights[tile]).sum();
+            let mut roll = prng.max(total);
+            let mut chosen = 0;
+            for tile in (0..tile_count).filter(|&tile| wave[index][tile]) {
+                if roll < rules.weights[tile] {
+                    chosen = tile;
+                    break;
+                }
+                roll -= rules.weights[tile];
+            }
+
+            for (tile, possible) in wave[index].iter_mut().enumerate() {
+                *possible = tile == chosen;
+            }
+            options[index] = 1;
+            stack.push(index);
+
+            // Remove anything the change made impossible.
+            while let Some(index) = stack.pop() {
+                let point = IVec2::new(
+                    (index % size.x as usize) as i32,
+                    (index / size.x as usize) as i32,
+                );
+                for direction in CardinalDirection::all() {
+                    let Some(neighbour) = (point + direction.coord()).as_index(size) else { continue; };
+
+                    let mut changed = false;
+                    for other in 0..tile_count {
+                        if !wave[neighbour][other] {
+                            continue;
+                        }
+
+                        let supported = (0..tile_count)
+                            .any(|tile| wave[index][tile] && rules.allowed[tile][direction as usize][other]);
+                        if !supported {
+                            wave[neighbour][other] = false;
+                            options[neighbour] -= 1;
+                            changed = true;
+                        }
+                    }
+
+                    if options[neighbour] == 0 {
+                        return None;
+                    }
+
+                    if changed {
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+
+        Some(wave.iter().map(|cell| cell.iter().position(|&possible| possible).unwrap_or(0)).collect())
+    }
+}
+
+impl<T> MapArchitect<T> for WaveFunctionCollapseBuilder<T> {
+    fn generate(&mut self, data: &mut MapGenData<T>) {
+        let rect = match &self.rect {
+            Some(r) => *r,
+            None => Rectangle::new((0i32, 0), data.size - UVec2::new(1, 1)),
+        };
+
+        if !data.terrain_grid.in_bounds(rect.min()) || !data.terrain_grid.in_bounds(rect.max()) {
+            error!(
+                "WaveFunctionCollapseBuilder Rectangle{{ {}, {} }} is outside of bounds for Grid({}, {})",
+                rect.min(),
+                rect.max(),
+                data.terrain_grid.width(),
+                data.terrain_grid.height()
+            );
+            return;
+        }
+
+        if self.samples.is_empty() {
+            error!("WaveFunctionCollapseBuilder has no samples to learn from");
+            return;
+        }
+
+        let rules = AdjacencyRules::learn(&self.samples);
+        let size = UVec2::new(rect.width() as u32 + 1, rect.height() as u32 + 1);
+
+        let tiles = Self::collapse_with_retries(&rules, size, &mut data.random.prng, self.max_retries);
+        let Some(tiles) = tiles else {
+            error!(
+                "WaveFunctionCollapseBuilder failed after {} retries, leaving terrain untouched",
+                self.max_retries
+            );
+            return;
+        };
+
+        rect.for_each(|v| {
+            let index = (v - rect.min()).as_index_unchecked(size.x);
+            data.terrain_grid.set(v, rules.values[tiles[index]]);
+        });
+    }
+}
+
+/// Which tiles may sit next to each other, learned from the samples.
+struct AdjacencyRules {
+    /// Every distinct value in the samples, indexed by tile.
+    values: Vec<u32>,
+    /// How often each tile appears in the samples.
+    weights: Vec<u32>,
+    /// `allowed[tile][direction][other]` is `true` if `other` may sit
+    /// on the `direction` side of `tile`.
+    allowed: Vec<[Vec<bool>; NUM_CARDINAL_DIRECTIONS]>,
+}
+
+impl AdjacencyRules {
+    fn learn(samples: &[WaveFunctionCollapseSample]) -> Self {
+        let mut values = Vec::new();
+        let mut weights = Vec::new();
+        for sample in samples {
+            Self::sample_points(sample).for_each(|point| {
+                let value = sample.get(point).unwrap_or_default();
+                match values.iter().position(|&v| v == value) {
+                    Some(tile) => weights[tile] += 1,
+                    None => {
+                        values.push(value);
+                        weights.push(1);
+                    },
+                }
+            });
+        }
+
+        let tile_count = values.len();
+        let tile_of = |value: u32| values.iter().position(|&v| v == value).unwrap_or_default();
+        let mut allowed = vec![
+            [
+                vec![false; tile_count],
+                vec![false; tile_count],
+                vec![false; tile_count],
+                vec![false; tile_count],
+            ];
+            tile_count
+        ];
+
+        for sample in samples {
+            Self::sample_points(sample).for_each(|point| {
+                let tile = tile_of(sample.get(point).unwrap_or_default());
+                for direction in CardinalDirection::all() {
+                    let neighbour = point + direction.coord();
+                    let neighbour = IVec2::new(
+                        neighbour.x.rem_euclid(sample.width() as i32),
+                        neighbour.y.rem_euclid(sample.height() as i32),
+                    );
+                    let other = tile_of(sample.get(neighbour).unwrap_or_default());
+                    allowed[tile][direction as usize][other] = true;
+                    allowed[other][direction.opposite() as usize][tile] = true;
+                }
+            });
+        }
+
+        Self {
+            values,
+            weights,
+            allowed,
+        }
+    }
+
+    fn sample_points(sample: &WaveFunctionCollapseSample) -> RectIter {
+        RectIter::new(
+            (0i32, 0),
+            (sample.width() as i32 - 1, sample.height() as i32 - 1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Walls on the left, floor on the right.
+    fn stripes() -> WaveFunctionCollapseSample { WaveFunctionCollapseSample::from_ascii("#.\n#.").unwrap() }
+
+    fn generate(sample: WaveFunctionCollapseSample, seed: u64) -> Grid<u32> {
+        let mut data = MapGenData::new(UVec2::new(12, 8), Random::new(seed), ());
+        WaveFunctionCollapseBuilder::new().with_sample(sample).generate(&mut data);
+        data.terrain_grid
+    }
+
+    #[test]
+    fn parses_samples() {
+        let ascii = WaveFunctionCollapseSample::from_ascii("\n  #.#\n\n  .2.\n").unwrap();
+        let ron = WaveFunctionCollapseSample::from_ron("(rows: [[1, 0, 1], [0, 2, 0]])").unwrap();
+        assert_eq!(ascii, ron);
+        assert_eq!((ascii.width(), ascii.height()), (3, 2));
+
+        // The last row is the bottom of the map.
+        assert_eq!(ascii.get((1, 0)), Some(2));
+        assert_eq!(ascii.get((0, 1)), Some(1));
+        assert_eq!(ascii.get((3, 0)), None);
+
+        assert!(WaveFunctionCollapseSample::from_ascii("").is_err());
+        assert!(WaveFunctionCollapseSample::from_ascii("#?#").is_err());
+        assert!(WaveFunctionCollapseSample::from_ascii("##\n#").is_err());
+        assert!(WaveFunctionCollapseSample::from_ron("(rows: [[1, 0], [1]])").is_err());
+        assert!(WaveFunctionCollapseSample::from_ron("[[1, 0]]").is_err());
+    }
+
+    #[test]
+    fn learns_adjacency_from_samples() {
+        let rules = AdjacencyRules::learn(&[stripes()]);
+        let tile_of = |value: u32| rules.values.iter().position(|&v| v == value).unwrap();
+        let (wall, floor) = (tile_of(1), tile_of(0));
+        assert_eq!(rules.weights, vec![2, 2]);
+
+        // Wrapping around, floor is on both sides of a wall.
+        let allowed = |tile: usize, direction: CardinalDirection, other: usize| {
+            rules.allowed[tile][direction as usize][other]
+        };
+        assert!(allowed(wall, CardinalDirection::East, floor));
+        assert!(allowed(wall, CardinalDirection::West, floor));
+        assert!(!allowed(wall, CardinalDirection::East, wall));
+        assert!(allowed(wall, CardinalDirection::North, wall));
+        assert!(!allowed(floor, CardinalDirection::South, wall));
+
+        // So the output is stripes too.
+        let terrain = generate(stripes(), 0);
+        for y in 0..8 {
+            for x in 0..11 {
+                assert_ne!(terrain.get((x, y)), terrain.get((x + 1, y)));
+            }
+        }
+        for y in 0..7 {
+            for x in 0..12 {
+                assert_eq!(terrain.get((x, y)), terrain.get((x, y + 1)));
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_map() {
+        let sample = WaveFunctionCollapseSample::from_ascii(
+            "
+            #####
+            #...#
+            #.#..
+            #...#
+            ##.##
+            ",
+        )
+        .unwrap();
+
+        for seed in 0..4 {
+            assert_eq!(
+                generate(sample.clone(), seed),
+                generate(sample.clone(), seed)
+            );
+        }
+    }
+
+    #[test]
+    fn retries_after_contradictions() {
+        // `1` may only sit east of `0`, so it's a coin flip whether a first pick
+        // of the wrong tile for its side leaves its neighbour with nothing.
+        let (neither, first, second) = (vec![false, false], vec![true, false], vec![false, true]);
+        let rules = AdjacencyRules {
+            values: vec![0, 1],
+            weights: vec![1, 1],
+            allowed: vec![
+                [neither.clone(), second, neither.clone(), neither.clone()],
+                [neither.clone(), neither.clone(), neither, first],
+            ],
+        };
+        let size = UVec2::new(2, 1);
+        let collapse = |seed: u64, max_retries: u32| {
+            WaveFunctionCollapseBuilder::<()>::collapse_with_retries(
+                &rules,
+                size,
+                &mut Random::new(seed).prng,
+                max_retries,
+            )
+        };
+
+        assert!((0..16).any(|seed| collapse(seed, 0).is_none()));
+        for seed in 0..16 {
+            assert_eq!(collapse(seed, 32), Some(vec![0, 1]));
+        }
+    }
+}
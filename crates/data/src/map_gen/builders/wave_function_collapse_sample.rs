@@ -0,0 +1,75 @@
+use crate::prelude::*;
+
+/// A small hand made map used to teach the `WaveFunctionCollapseBuilder`
+/// which values may sit next to each other.
+///
+/// Rows are stored top to bottom, the same way they are written in a file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WaveFunctionCollapseSample {
+    rows: Vec<Vec<u32>>,
+}
+
+impl WaveFunctionCollapseSample {
+    /// Build a sample from a grid of values, rows top to bottom.
+    pub fn new(rows: Vec<Vec<u32>>) -> AtrlResult<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if width == 0 {
+            return Err(AtrlError::InvalidSample("sample is empty".to_string()));
+        }
+
+        if let Some(y) = rows.iter().position(|row| row.len() != width) {
+            return Err(AtrlError::InvalidSample(format!(
+                "row {} has {} columns, expected {}",
+                y,
+                rows[y].len(),
+                width
+            )));
+        }
+
+        Ok(Self { rows })
+    }
+
+    /// Parse an ASCII sample:
+    /// `.` is `0` (floor), `#` is `1` (wall) and `0-9` are taken as is.
+    /// Blank lines are ignored.
+    pub fn from_ascii(ascii: &str) -> AtrlResult<Self> {
+        let mut rows = Vec::new();
+        for line in ascii.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let mut row = Vec::with_capacity(line.len());
+            for c in line.chars() {
+                let value = match c {
+                    '.' => 0,
+                    '#' => 1,
+                    c => c.to_digit(10).ok_or_else(|| {
+                        AtrlError::InvalidSample(format!("unknown character '{}' in ascii sample", c))
+                    })?,
+                };
+                row.push(value);
+            }
+            rows.push(row);
+        }
+
+        Self::new(rows)
+    }
+
+    /// Parse a RON sample: `(rows: [[1, 1, 1], [1, 0, 1], [1, 1, 1]])`
+    pub fn from_ron(text: &str) -> AtrlResult<Self> {
+        let sample: Self =
+            ron::from_str(text).map_err(|e| AtrlError::InvalidSample(format!("invalid ron sample: {}", e)))?;
+        Self::new(sample.rows)
+    }
+
+    pub fn width(&self) -> u32 { self.rows[0].len() as u32 }
+
+    pub fn height(&self) -> u32 { self.rows.len() as u32 }
+
+    /// Value at a grid point, where `y = 0` is the bottom row like the rest of the map.
+    pub fn get(&self, point: impl GridPoint) -> Option<u32> {
+        if !point.is_valid(UVec2::new(self.width(), self.height())) {
+            return None;
+        }
+
+        let row = self.height() as usize - 1 - point.y() as usize;
+        Some(self.rows[row][point.x() as usize])
+    }
+}
@@ -0,0 +1,32 @@
+use crate::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitType {
+    /// Opening on the edge of the map leading to the neighbouring map in that direction.
+    Edge(CardinalDirection),
+    /// Leads to the map at `z + 1`.
+    StairsUp,
+    /// Leads to the map at `z - 1`.
+    StairsDown,
+}
+
+/// A tile that leads onto another map.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MapExit {
+    pub position: UVec2,
+    pub exit_type: ExitType,
+    pub destination: WorldPosition,
+}
+
+impl ExitType {
+    /// `WorldPosition` of the map this exit leads to.
+    pub fn destination(&self, world_position: WorldPosition) -> WorldPosition {
+        let offset = match self {
+            Self::Edge(direction) => direction.coord().extend(0),
+            Self::StairsUp => IVec3::Z,
+            Self::StairsDown => IVec3::NEG_Z,
+        };
+        let xyz = world_position.xyz() + offset;
+        WorldPosition::new(xyz.x, xyz.y, xyz.z)
+    }
+}
@@ -5,10 +5,13 @@ pub struct MapGenData<T> {
 
     pub size: UVec2,
     pub random: Random,
-    pub exit_positions: Vec<UVec2>,
+    pub exit_positions: Vec<MapExit>,
 
     pub terrain_grid: Grid<u32>,
     pub rooms: Vec<Rectangle>,
+
+    /// Region id for each cell, `0` is unassigned. See `VoronoiBuilder`.
+    pub region_grid: Grid<u32>,
 }
 
 impl<T> MapGenData<T> {
@@ -20,6 +23,28 @@ impl<T> MapGenData<T> {
             exit_positions: Vec::new(),
             terrain_grid: Grid::new_default(size),
             rooms: Vec::new(),
+            region_grid: Grid::new_default(size),
+        }
+    }
+
+    /// Smallest `Rectangle` containing every cell of a region,
+    /// handy for running another builder over just that region.
+    ///
+    /// Returns `None` if no cell belongs to the region.
+    pub fn region_bounds(&self, region: u32) -> Option<Rectangle> {
+        let mut bounds: Option<(IVec2, IVec2)> = None;
+        for y in 0..self.size.y as i32 {
+            for x in 0..self.size.x as i32 {
+                let point = IVec2::new(x, y);
+                if *self.region_grid.get_unchecked(point) != region {
+                    continue;
+                }
+                bounds = Some(match bounds {
+                    Some((min, max)) => (min.min(point), max.max(point)),
+                    None => (point, point),
+                });
+            }
         }
+        bounds.map(|(min, max)| Rectangle::new(min, max))
     }
 }
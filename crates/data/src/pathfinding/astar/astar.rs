@@ -1,6 +1,11 @@
-use super::{super::shared::*, astar_node::*};
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use super::super::shared::*;
 use crate::prelude::*;
 
+const CARDINAL_COST: u32 = 10;
+const ORDINAL_COST: u32 = 14;
+
 pub struct AStar;
 
 impl PathAlgorithm for AStar {
@@ -12,85 +17,75 @@ impl PathAlgorithm for AStar {
         provider: &mut impl PathProvider,
         q_blocks_movement: &Query<&BlocksMovement>,
     ) -> Option<Vec<Position>> {
-        // create open/closed lists
-        let mut open_nodes = IndexList::new();
-        let mut closed_nodes = IndexList::new();
+        let mut scores: HashMap<Position, AStarScore> = HashMap::new();
+        let mut open_nodes = BinaryHeap::new();
+        let mut sequence = 0;
 
         // add the first node to the open list before starting the loop
-        let first_node = AStarNode::new(origin, destination);
-        open_nodes.insert_first(first_node);
-
-        // loop through all the nodes
-        // return if path is found
-        loop {
-            if open_nodes.is_empty() {
-                break;
+        scores.insert(origin, AStarScore::origin());
+        open_nodes.push(OpenNode::new(origin, 0, destination, sequence));
+
+        // the closed node nearest the destination, for partial paths
+        let mut closest: Option<(u32, Position)> = None;
+        while let Some(current_node) = open_nodes.pop() {
+            let Some(score) = scores.get(&current_node.position) else { continue; };
+
+            // This node was queued again with a lower cost since this entry was pushed.
+            if score.is_closed || score.cost_from_start != current_node.cost_from_start {
+                continue;
             }
 
-            // get the lowest cost node
-            if let Some(current_node) = open_nodes.remove_first() {
-                if current_node.position() == destination {
-                    return Self::reconstruct_path(current_node, &mut closed_nodes);
-                }
+            if current_node.position == destination {
+                return Some(Self::reconstruct_path(current_node.position, &scores));
+            }
 
-                // update cardinals
-                for cardinal in CardinalDirection::all() {
-                    let current_position = current_node.position() + cardinal.coord();
-                    current_node.update_at_position(
-                        current_position,
-                        false,
-                        destination,
-                        provider,
-                        q_blocks_movement,
-                        movement_type,
-                        &mut open_nodes,
-                        &mut closed_nodes,
-                    );
+            // update cardinals, then ordinals
+            let neighbours = CardinalDirection::all()
+                .map(|cardinal| (cardinal.coord(), CARDINAL_COST))
+                .chain(OrdinalDirection::all().map(|ordinal| (ordinal.coord(), ORDINAL_COST)));
+            for (coord, step_cost) in neighbours {
+                let position = current_node.position + coord;
+                let neighbour = scores.entry(position).or_insert_with(|| {
+                    AStarScore::new(
+                        provider.is_walkable(position, movement_type, q_blocks_movement),
+                        provider.cost(position, movement_type),
+                    )
+                });
+
+                if !neighbour.is_walkable || neighbour.is_closed {
+                    continue;
                 }
 
-                // update ordinals
-                for ordinal in OrdinalDirection::all() {
-                    let current_position = current_node.position() + ordinal.coord();
-                    current_node.update_at_position(
-                        current_position,
-                        true,
+                let new_cost_from_start =
+                    current_node.cost_from_start + step_cost * neighbour.cost_multiplier;
+                if new_cost_from_start < neighbour.cost_from_start {
+                    neighbour.cost_from_start = new_cost_from_start;
+                    neighbour.from_node = Some(current_node.position);
+
+                    sequence += 1;
+                    open_nodes.push(OpenNode::new(
+                        position,
+                        new_cost_from_start,
                         destination,
-                        provider,
-                        q_blocks_movement,
-                        movement_type,
-                        &mut open_nodes,
-                        &mut closed_nodes,
-                    );
+                        sequence,
+                    ));
                 }
+            }
 
-                // close the current node
-                closed_nodes.insert_last(current_node);
+            // close the current node
+            if let Some(score) = scores.get_mut(&current_node.position) {
+                score.is_closed = true;
+            }
+            if closest.map_or(true, |(cost_from_end, _)| {
+                current_node.cost_from_end < cost_from_end
+            }) {
+                closest = Some((current_node.cost_from_end, current_node.position));
             }
         }
 
         // No path found.
         if partial_path_on_failure {
-            let mut index = closed_nodes.first_index();
-            let mut best_node_index = index;
-
-            if let Some(best_node) = closed_nodes.get(best_node_index) {
-                let mut best_cost = best_node.get_cost_from_end();
-                index = closed_nodes.next_index(index);
-                while index.is_some() {
-                    if let Some(current_node) = closed_nodes.get(index) {
-                        let current_cost = current_node.get_cost_from_end();
-                        if best_cost > current_cost {
-                            best_node_index = index;
-                            best_cost = current_cost;
-                        }
-                    }
-                    index = closed_nodes.next_index(index);
-                }
-            }
-
-            closed_nodes
-                .remove(best_node_index)
-                .and_then(|best_node| Self::reconstruct_path(best_node, &mut closed_nodes))
+            closest.map(|(_, position)| Self::reconstruct_path(position, &scores))
         } else {
             None
         }
@@ -100,25 +95,172 @@ impl PathAlgorithm for AStar {
 impl AStar {
     /// This will return a path *WITHOUT* the starting point. It also
     /// does not reverse the path, so it will be in the order of last point -> first point.
-    fn reconstruct_path(
-        finished_node: AStarNode,
-        closed_nodes: &mut IndexList<AStarNode>,
-    ) -> Option<Vec<Position>> {
+    fn reconstruct_path(finished: Position, scores: &HashMap<Position, AStarScore>) -> Vec<Position> {
         let mut ret = Vec::new();
-        let mut current_node = finished_node;
-        loop {
-            current_node = match current_node.get_from_node() {
-                None => {
-                    // ret.reverse();
-                    return Some(ret);
-                },
-                Some(position) => {
-                    ret.push(current_node.position());
-                    match AStarNode::find_node_with_position(closed_nodes, position) {
-                        None => return None,
-                        Some(index) => closed_nodes.remove(index).unwrap(),
-                    }
-                },
+        let mut current = finished;
+        while let Some(from_node) = scores.get(&current).and_then(|score| score.from_node) {
+            ret.push(current);
+            current = from_node;
+        }
+        ret
+    }
+}
+
+/// Everything known about a position the search has looked at.
+struct AStarScore {
+    is_walkable: bool,
+    is_closed: bool,
+    cost_multiplier: u32,
+    cost_from_start: u32,
+    from_node: Option<Position>,
+}
+
+impl AStarScore {
+    const fn new(is_walkable: bool, cost_multiplier: u32) -> Self {
+        Self {
+            is_walkable,
+            is_closed: false,
+            cost_multiplier,
+            cost_from_start: u32::MAX,
+            from_node: None,
+        }
+    }
+
+    const fn origin() -> Self {
+        Self {
+            cost_from_start: u32::MIN,
+            ..Self::new(true, 0) // we are already here
+        }
+    }
+}
+
+/// An entry in the open list. Positions are pushed again whenever a cheaper way
+/// to them is found, leaving the old entry to be skipped when it's popped.
+struct OpenNode {
+    position: Position,
+    cost_from_start: u32,
+    cost_from_end: u32,
+    cost_total: u32,
+    sequence: u32,
+}
+
+impl OpenNode {
+    fn new(position: Position, cost_from_start: u32, destination: Position, sequence: u32) -> Self {
+        let cost_from_end = position.distance(destination);
+        Self {
+            position,
+            cost_from_start,
+            cost_from_end,
+            cost_total: cost_from_start + cost_from_end,
+            sequence,
+        }
+    }
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Lowest total first, breaking ties with the node closest to the goal.
+        // Any tie left goes to the most recently pushed node.
+        other
+            .cost_total
+            .cmp(&self.cost_total)
+            .then(other.cost_from_end.cmp(&self.cost_from_end))
+            .then(self.sequence.cmp(&other.sequence))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::index_list_astar::IndexListAStar, *};
+
+    const SIZE: u32 = 24;
+
+    /// A single map with random walls and random costs.
+    struct Maze {
+        walls: Grid<bool>,
+        costs: Grid<u32>,
+    }
+
+    impl Maze {
+        fn new(seed: u64) -> Self {
+            let mut random = Random::new(seed);
+            let mut walls = Grid::new_default(UVec2::splat(SIZE));
+            let mut costs = Grid::new_copy(UVec2::splat(SIZE), 1);
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    walls.set((x, y), random.prng.max(100) < 30);
+                    costs.set((x, y), random.prng.range(1..4));
+                }
+            }
+            Self { walls, costs }
+        }
+    }
+
+    impl PathProvider for Maze {
+        fn is_walkable(&mut self, position: Position, _: u8, _: &Query<&BlocksMovement>) -> bool {
+            position.get_world_position() == WorldPosition::ZERO &&
+                self.walls.get(position.get_local_position().gridpoint()) == Some(&false)
+        }
+
+        fn cost(&mut self, position: Position, _: u8) -> u32 {
+            self.costs.get(position.get_local_position().gridpoint()).copied().unwrap_or(1)
+        }
+    }
+
+    fn position(x: u32, y: u32) -> Position {
+        Position::new(
+            WorldPosition::ZERO,
+            LocalPosition::new(x, y, MapLayer::Terrain as u32),
+        )
+    }
+
+    #[test]
+    fn matches_index_list_astar() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        for seed in 0..16 {
+            let mut maze = Maze::new(seed);
+            let mut random = Random::new(seed);
+            for _ in 0..8 {
+                let origin = position(random.prng.max(SIZE), random.prng.max(SIZE));
+                let destination = position(random.prng.max(SIZE), random.prng.max(SIZE));
+                for partial in [false, true] {
+                    assert_eq!(
+                        AStar::compute_path(
+                            origin,
+                            destination,
+                            0,
+                            partial,
+                            &mut maze,
+                            &q_blocks_movement
+                        ),
+                        IndexListAStar::compute_path(
+                            origin,
+                            destination,
+                            0,
+                            partial,
+                            &mut maze,
+                            &q_blocks_movement
+                        ),
+                        "seed {} from {} to {} (partial: {})",
+                        seed,
+                        origin,
+                        destination,
+                        partial,
+                    );
+                }
             }
         }
     }
@@ -0,0 +1,286 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::prelude::*;
+
+const CARDINAL_COST: f32 = 1.0;
+
+/// Scale applied to a chase map to turn it into a flee map.
+/// Anything below `-1.0` makes fleeing actors prefer a long way round
+/// over being cornered next to the goals.
+pub const FLEE_FACTOR: f32 = -1.2;
+
+/// A distance field flowing towards one or more weighted goals.
+///
+/// Every reached position holds the cheapest cost to get from it to a goal,
+/// plus that goal's weight, so lower weights are more attractive.
+/// Actors follow the map by stepping [`DijkstraMap::downhill`].
+///
+/// Positions further than `max_cost` from every goal are never stored and
+/// read as [`DijkstraMap::unreached`].
+#[derive(Debug, Clone)]
+pub struct DijkstraMap {
+    movement_type: u8,
+    max_cost: f32,
+    unreached: f32,
+    values: HashMap<Position, f32>,
+}
+
+impl DijkstraMap {
+    pub fn new(movement_type: u8, max_cost: f32) -> Self {
+        Self {
+            movement_type,
+            max_cost,
+            unreached: max_cost,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Build and scan a map flowing towards `goals` in one go.
+    pub fn compute(
+        goals: impl IntoIterator<Item = (Position, f32)>,
+        movement_type: u8,
+        max_cost: f32,
+        provider: &mut impl PathProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Self {
+        let mut map = Self::new(movement_type, max_cost);
+        for (position, weight) in goals {
+            map.add_goal(position, weight);
+        }
+        map.scan(provider, q_blocks_movement);
+        map
+    }
+
+    pub const fn movement_type(&self) -> u8 { self.movement_type }
+
+    /// Value of positions outside of the scanned area.
+    pub const fn unreached(&self) -> f32 { self.unreached }
+
+    pub fn get(&self, position: Position) -> Option<f32> { self.values.get(&position).copied() }
+
+    /// Value at `position`, or [`DijkstraMap::unreached`] if it was never scanned.
+    pub fn value(&self, position: Position) -> f32 { self.get(position).unwrap_or(self.unreached) }
+
+    pub fn len(&self) -> usize { self.values.len() }
+
+    pub fn is_empty(&self) -> bool { self.values.is_empty() }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, f32)> + '_ {
+        self.values.iter().map(|(&position, &value)| (position, value))
+    }
+
+    /// Add a goal, keeping the lower weight if `position` is already a goal.
+    /// Call [`DijkstraMap::scan`] once all goals are added.
+    pub fn add_goal(&mut self, position: Position, weight: f32) {
+        let value = self.values.entry(position).or_insert(weight);
+        *value = value.min(weight);
+    }
+
+    pub fn clear(&mut self) { self.values.clear(); }
+
+    /// Flood outwards from every stored position, lowering neighbours
+    /// until nothing cheaper can be found or `max_cost` is reached.
+    pub fn scan(&mut self, provider: &mut impl PathProvider, q_blocks_movement: &Query<&BlocksMovement>) {
+        let mut open: BinaryHeap<_> = self
+            .values
+            .iter()
+            .map(|(&position, &value)| ScanNode {
+                value,
+                travelled: 0.0,
+                position,
+            })
+            .collect();
+
+        while let Some(ScanNode {
+            value,
+            travelled,
+            position,
+        }) = open.pop()
+        {
+            // A cheaper route has already been through here.
+            if self.values.get(&position).map_or(false, |&current| current < value) {
+                continue;
+            }
+
+            for direction in GridDirection::all() {
+                let neighbour = position + direction.coord();
+                if !provider.is_walkable(neighbour, self.movement_type, q_blocks_movement) {
+                    continue;
+                }
+
+                let step = if direction.is_ordinal() { DIAGONAL_COST } else { CARDINAL_COST } *
+                    provider.cost(neighbour, self.movement_type) as f32;
+                let travelled = travelled + step;
+                let new_value = value + step;
+                match self.values.get(&neighbour) {
+                    Some(&current) if current <= new_value => continue,
+                    None if travelled > self.max_cost => continue,
+                    _ => {},
+                }
+
+                self.values.insert(neighbour, new_value);
+                open.push(ScanNode {
+                    value: new_value,
+                    travelled,
+                    position: neighbour,
+                });
+            }
+        }
+    }
+
+    /// Multiply every value by `factor`.
+    pub fn scale(&mut self, factor: f32) {
+        self.values.values_mut().for_each(|value| *value *= factor);
+        self.unreached *= factor;
+    }
+
+    /// Add `other` onto this map. Positions only one of the maps reached
+    /// use the other's [`DijkstraMap::unreached`] value.
+    pub fn add(&mut self, other: &Self) {
+        for value in self.values.values_mut() {
+            *value += other.unreached;
+        }
+        for (&position, &other_value) in &other.values {
+            let value = self.values.entry(position).or_insert(self.unreached + other.unreached);
+            *value += other_value - other.unreached;
+        }
+        self.unreached += other.unreached;
+    }
+
+    /// Weighted sum of several maps, all of which must share a movement type.
+    pub fn sum<'a>(maps: impl IntoIterator<Item = (&'a Self, f32)>) -> Option<Self> {
+        let mut maps = maps.into_iter();
+        let (first, first_factor) = maps.next()?;
+
+        let mut total = first.clone();
+        total.scale(first_factor);
+        for (map, factor) in maps {
+            let mut map = map.clone();
+            map.scale(factor);
+            total.add(&map);
+        }
+        Some(total)
+    }
+
+    /// Turn a map flowing towards its goals into one flowing away from them.
+    ///
+    /// Simply negating the map would send actors into the nearest corner,
+    /// so the values are scaled by `factor` (see [`FLEE_FACTOR`]) and rescanned,
+    /// letting actors slip past the goals towards a better escape.
+    pub fn flee(
+        &self,
+        factor: f32,
+        provider: &mut impl PathProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Self {
+        let mut flee = self.clone();
+        flee.scale(factor);
+        flee.max_cost = 0.0;
+        flee.scan(provider, q_blocks_movement);
+        flee.max_cost = self.max_cost;
+        flee
+    }
+
+    /// The walkable neighbour of `position` with the lowest value,
+    /// or `None` if `position` is already at the bottom.
+    pub fn downhill(
+        &self,
+        position: Position,
+        provider: &mut impl PathProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Option<Position> {
+        let mut best = (self.value(position), None);
+        for direction in GridDirection::all() {
+            let neighbour = position + direction.coord();
+            let Some(value) = self.get(neighbour) else { continue; };
+            if value < best.0 && provider.is_walkable(neighbour, self.movement_type, q_blocks_movement) {
+                best = (value, Some(neighbour));
+            }
+        }
+        best.1
+    }
+}
+
+/// An open position waiting to be scanned, ordered so the lowest value pops first.
+struct ScanNode {
+    value: f32,
+    travelled: f32,
+    position: Position,
+}
+
+impl PartialEq for ScanNode {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for ScanNode {}
+
+impl PartialOrd for ScanNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for ScanNode {
+    fn cmp(&self, other: &Self) -> Ordering { OrderedFloat(other.value).cmp(&OrderedFloat(self.value)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An open 10x10 room.
+    struct Room;
+
+    impl PathProvider for Room {
+        fn is_walkable(&mut self, position: Position, _: u8, _: &Query<&BlocksMovement>) -> bool {
+            position.get_world_position() == WorldPosition::ZERO && position.x() < 10 && position.y() < 10
+        }
+
+        fn cost(&mut self, _: Position, _: u8) -> u32 { 1 }
+    }
+
+    fn position(x: u32, y: u32) -> Position {
+        Position::new(
+            WorldPosition::ZERO,
+            LocalPosition::new(x, y, MapLayer::Terrain as u32),
+        )
+    }
+
+    #[test]
+    fn downhill_leads_to_the_cheapest_goal() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        let goals = [(position(0, 5), 0.0), (position(9, 5), 3.0)];
+        let map = DijkstraMap::compute(goals, 0, 20.0, &mut Room, &q_blocks_movement);
+        assert_eq!(map.get(position(0, 5)), Some(0.0));
+        assert_eq!(map.get(position(3, 5)), Some(3.0));
+
+        // (5, 5) is 5 away from the first goal, but 4 + 3 from the second.
+        assert_eq!(
+            map.downhill(position(5, 5), &mut Room, &q_blocks_movement),
+            Some(position(4, 5))
+        );
+        assert_eq!(
+            map.downhill(position(0, 5), &mut Room, &q_blocks_movement),
+            None
+        );
+    }
+
+    #[test]
+    fn flee_leads_away_from_the_goal() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        let map = DijkstraMap::compute(
+            [(position(5, 5), 0.0)],
+            0,
+            20.0,
+            &mut Room,
+            &q_blocks_movement,
+        );
+        let flee = map.flee(FLEE_FACTOR, &mut Room, &q_blocks_movement);
+        let next = flee.downhill(position(6, 5), &mut Room, &q_blocks_movement).unwrap();
+        assert!(next.distance(position(5, 5)) > position(6, 5).distance(position(5, 5)));
+    }
+}
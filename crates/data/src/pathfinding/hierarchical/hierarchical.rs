@@ -0,0 +1,525 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use super::super::shared::*;
+use crate::prelude::*;
+
+const CARDINAL_COST: u32 = 10;
+const ORDINAL_COST: u32 = 14;
+
+/// Give up on routes that wander through more maps than this.
+const MAX_SEARCHED_MAPS: usize = 64;
+
+/// Plans routes between maps over a graph of their exits, then refines
+/// each leg with `AStar` once the actor gets there.
+///
+/// Walking between two exits of a loaded map is costed with `AStar`, kept inside
+/// that map. Maps that aren't loaded are never generated, the walk across them
+/// is estimated from where their exits will be. Crossing an edge exit is a single
+/// step onto the neighbouring map, and stairs lead to the matching stairs above / below.
+pub struct HierarchicalPathFinder;
+
+impl HierarchicalPathFinder {
+    /// Works like `PathFinder::compute`, but when `destination` is on another map only the
+    /// leg to the next waypoint is returned, in the order of last point -> first point.
+    pub fn compute(
+        origin: Position,
+        destination: Position,
+        movement_type: u8,
+        partial_path_on_failure: bool,
+        provider: &mut impl MapGraphProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Option<Vec<Position>> {
+        if origin.get_world_position() == destination.get_world_position() {
+            return AStar::compute_path(
+                origin,
+                destination,
+                movement_type,
+                partial_path_on_failure,
+                provider,
+                q_blocks_movement,
+            );
+        }
+
+        match Self::plan(
+            origin,
+            destination,
+            movement_type,
+            provider,
+            q_blocks_movement,
+        ) {
+            Some(mut path) => path.refine_next_leg(origin, movement_type, provider, q_blocks_movement),
+            // Get as close as we can without leaving this map.
+            None if partial_path_on_failure => AStar::compute_path(
+                origin,
+                destination,
+                movement_type,
+                true,
+                &mut MapBoundProvider::new(provider, [origin.get_world_position(); 2]),
+                q_blocks_movement,
+            ),
+            None => None,
+        }
+    }
+
+    /// Find the exits to pass through on the way to `destination`.
+    ///
+    /// Returns `None` if no route was found within `MAX_SEARCHED_MAPS` maps.
+    pub fn plan(
+        origin: Position,
+        destination: Position,
+        movement_type: u8,
+        provider: &mut impl MapGraphProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Option<HierarchicalPath> {
+        // position -> (cost from start, came from)
+        let mut nodes: HashMap<Position, (u32, Option<Position>)> = HashMap::new();
+        let mut closed = HashSet::new();
+        let mut searched_maps = HashSet::new();
+        let mut open = BinaryHeap::new();
+
+        nodes.insert(origin, (0, None));
+        open.push(PlanNode::new(origin, 0, destination));
+
+        while let Some(PlanNode { position, cost, .. }) = open.pop() {
+            if position == destination {
+                return Some(HierarchicalPath::new(Self::reconstruct_plan(
+                    position, &nodes,
+                )));
+            }
+
+            if !closed.insert(position) {
+                continue;
+            }
+
+            searched_maps.insert(position.get_world_position());
+            if searched_maps.len() > MAX_SEARCHED_MAPS {
+                break;
+            }
+
+            for (next, edge_cost) in Self::edges(
+                position,
+                destination,
+                movement_type,
+                provider,
+                q_blocks_movement,
+            ) {
+                if closed.contains(&next) {
+                    continue;
+                }
+
+                let new_cost = cost + edge_cost;
+                if nodes.get(&next).map_or(true, |&(current, _)| new_cost < current) {
+                    nodes.insert(next, (new_cost, Some(position)));
+                    open.push(PlanNode::new(next, new_cost, destination));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Everywhere reachable from `position` in one leg, and what it costs to get there.
+    fn edges(
+        position: Position,
+        destination: Position,
+        movement_type: u8,
+        provider: &mut impl MapGraphProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Vec<(Position, u32)> {
+        let world_position = position.get_world_position();
+        let exits = provider.get_exits(world_position);
+        let mut edges = Vec::new();
+
+        // Standing on an exit, so cross over to wherever it leads.
+        if let Some(exit) = exits.iter().find(|exit| exit.position == position.gridpoint()) {
+            if let Some(arrival) = Self::cross(position, exit, provider) {
+                edges.push((arrival, CARDINAL_COST));
+            }
+        }
+
+        // Walk to any other exit on this map, or the destination if it's here.
+        let targets = exits
+            .iter()
+            .map(|exit| Self::position_on(world_position, exit.position, position.layer()))
+            .chain((destination.get_world_position() == world_position).then_some(destination));
+        for target in targets {
+            if target == position {
+                continue;
+            }
+
+            if let Some(cost) = Self::leg_cost(
+                position,
+                target,
+                target == destination,
+                movement_type,
+                provider,
+                q_blocks_movement,
+            ) {
+                edges.push((target, cost));
+            }
+        }
+
+        edges
+    }
+
+    /// Where taking `exit` from `position` arrives.
+    fn cross(position: Position, exit: &MapExit, provider: &mut impl MapGraphProvider) -> Option<Position> {
+        let arrival_type = match exit.exit_type {
+            ExitType::Edge(direction) => return Some(position + direction.coord()),
+            ExitType::StairsUp => ExitType::StairsDown,
+            ExitType::StairsDown => ExitType::StairsUp,
+        };
+
+        provider
+            .get_exits(exit.destination)
+            .into_iter()
+            .find(|arrival| arrival.exit_type == arrival_type)
+            .map(|arrival| Self::position_on(exit.destination, arrival.position, position.layer()))
+    }
+
+    /// Cost of walking from `from` to `to` on the same map.
+    ///
+    /// `allow_adjacent` accepts ending next to `to`, for destinations
+    /// that are occupied by whatever is being chased.
+    fn leg_cost(
+        from: Position,
+        to: Position,
+        allow_adjacent: bool,
+        movement_type: u8,
+        provider: &mut impl MapGraphProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Option<u32> {
+        let world_position = from.get_world_position();
+        if !provider.is_loaded(world_position) {
+            return Some(Self::estimate(from, to));
+        }
+
+        let path = AStar::compute_path(
+            from,
+            to,
+            movement_type,
+            allow_adjacent,
+            &mut MapBoundProvider::new(provider, [world_position; 2]),
+            q_blocks_movement,
+        )?;
+
+        let end = path.first().copied().unwrap_or(from);
+        if end == to {
+            Some(Self::path_cost(from, &path, movement_type, provider))
+        } else if allow_adjacent && end.distance(to) <= 1 {
+            Some(Self::path_cost(from, &path, movement_type, provider) + Self::estimate(end, to))
+        } else {
+            None
+        }
+    }
+
+    /// Cost of a path returned by `AStar`, which runs from last point -> first point.
+    /// Each step is scaled by the provider's cost of the tile stepped onto, as in `AStar`.
+    fn path_cost(
+        from: Position,
+        path: &[Position],
+        movement_type: u8,
+        provider: &mut impl MapGraphProvider,
+    ) -> u32 {
+        let mut cost = 0;
+        let mut current = from;
+        for &next in path.iter().rev() {
+            cost += Self::estimate(current, next) * provider.cost(next, movement_type);
+            current = next;
+        }
+        cost
+    }
+
+    /// Octile distance, scaled the same as the step costs.
+    fn estimate(from: Position, to: Position) -> u32 {
+        let offset = from.offset_to(to).abs();
+        let (min, max) = (offset.x.min(offset.y) as u32, offset.x.max(offset.y) as u32);
+        CARDINAL_COST * (max - min) + ORDINAL_COST * min
+    }
+
+    fn position_on(world_position: WorldPosition, point: UVec2, layer: u32) -> Position {
+        Position::new(world_position, LocalPosition::new(point.x, point.y, layer))
+    }
+
+    /// Waypoints in the order they are visited, *WITHOUT* the starting point.
+    fn reconstruct_plan(
+        finished: Position,
+        nodes: &HashMap<Position, (u32, Option<Position>)>,
+    ) -> VecDeque<Position> {
+        let mut waypoints = VecDeque::new();
+        let mut current = finished;
+        while let Some(&(_, Some(from))) = nodes.get(&current) {
+            waypoints.push_front(current);
+            current = from;
+        }
+        waypoints
+    }
+}
+
+/// A route across maps from `HierarchicalPathFinder::plan`, made of the exits
+/// to pass through and the destination. Each leg is only walked out tile by tile
+/// with [`HierarchicalPath::refine_next_leg`] once the actor is on that map.
+#[derive(Debug, Clone, Default)]
+pub struct HierarchicalPath {
+    waypoints: VecDeque<Position>,
+}
+
+impl HierarchicalPath {
+    fn new(waypoints: VecDeque<Position>) -> Self { Self { waypoints } }
+
+    pub fn waypoints(&self) -> impl Iterator<Item = &Position> { self.waypoints.iter() }
+
+    pub fn next_waypoint(&self) -> Option<Position> { self.waypoints.front().copied() }
+
+    pub fn destination(&self) -> Option<Position> { self.waypoints.back().copied() }
+
+    pub fn is_empty(&self) -> bool { self.waypoints.is_empty() }
+
+    /// Path from `from` to the next waypoint not yet reached, in the order of
+    /// last point -> first point like `PathFinder::compute`.
+    ///
+    /// Taking the stairs is a single step onto the stairs at the other end.
+    pub fn refine_next_leg(
+        &mut self,
+        from: Position,
+        movement_type: u8,
+        provider: &mut impl PathProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Option<Vec<Position>> {
+        while self.waypoints.front() == Some(&from) {
+            self.waypoints.pop_front();
+        }
+
+        let next = self.next_waypoint()?;
+        if next.get_world_position().z() != from.get_world_position().z() {
+            return Some(vec![next]);
+        }
+
+        AStar::compute_path(
+            from,
+            next,
+            movement_type,
+            true,
+            &mut MapBoundProvider::new(provider, [
+                from.get_world_position(),
+                next.get_world_position(),
+            ]),
+            q_blocks_movement,
+        )
+    }
+}
+
+/// Keeps a search inside a couple of maps, so it never loads the maps around them.
+struct MapBoundProvider<'a, P: PathProvider> {
+    provider: &'a mut P,
+    world_positions: [WorldPosition; 2],
+}
+
+impl<'a, P: PathProvider> MapBoundProvider<'a, P> {
+    fn new(provider: &'a mut P, world_positions: [WorldPosition; 2]) -> Self {
+        Self {
+            provider,
+            world_positions,
+        }
+    }
+}
+
+impl<'a, P: PathProvider> PathProvider for MapBoundProvider<'a, P> {
+    fn is_walkable(
+        &mut self,
+        position: Position,
+        movement_type: u8,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> bool {
+        self.world_positions.contains(&position.get_world_position()) &&
+            self.provider.is_walkable(position, movement_type, q_blocks_movement)
+    }
+
+    fn cost(&mut self, position: Position, movement_type: u8) -> u32 {
+        self.provider.cost(position, movement_type)
+    }
+
+    fn has_uniform_cost(&mut self, movement_type: u8) -> bool {
+        self.provider.has_uniform_cost(movement_type)
+    }
+}
+
+/// An open waypoint, ordered so the lowest estimated total pops first.
+struct PlanNode {
+    position: Position,
+    cost: u32,
+    cost_total: u32,
+}
+
+impl PlanNode {
+    fn new(position: Position, cost: u32, destination: Position) -> Self {
+        Self {
+            position,
+            cost,
+            cost_total: cost + HierarchicalPathFinder::estimate(position, destination),
+        }
+    }
+}
+
+impl PartialEq for PlanNode {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for PlanNode {}
+
+impl PartialOrd for PlanNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for PlanNode {
+    fn cmp(&self, other: &Self) -> Ordering { other.cost_total.cmp(&self.cost_total) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Open maps in a row along x, each joined to the next by an edge exit halfway up.
+    /// Only `loaded` maps may be searched tile by tile, and every tile costs `cost`.
+    struct Row {
+        loaded: HashSet<WorldPosition>,
+        searched: HashSet<WorldPosition>,
+        cost: u32,
+    }
+
+    impl Row {
+        fn new(loaded: &[WorldPosition]) -> Self {
+            Self {
+                loaded: loaded.iter().copied().collect(),
+                searched: HashSet::new(),
+                cost: 1,
+            }
+        }
+    }
+
+    impl PathProvider for Row {
+        fn is_walkable(&mut self, position: Position, _: u8, _: &Query<&BlocksMovement>) -> bool {
+            self.searched.insert(position.get_world_position());
+            true
+        }
+
+        fn cost(&mut self, _: Position, _: u8) -> u32 { self.cost }
+    }
+
+    impl MapGraphProvider for Row {
+        fn get_exits(&mut self, world_position: WorldPosition) -> Vec<MapExit> {
+            [
+                (
+                    UVec2::new(GRID_WIDTH - 1, GRID_HEIGHT / 2),
+                    CardinalDirection::East,
+                ),
+                (UVec2::new(0, GRID_HEIGHT / 2), CardinalDirection::West),
+            ]
+            .into_iter()
+            .map(|(position, direction)| MapExit {
+                position,
+                exit_type: ExitType::Edge(direction),
+                destination: ExitType::Edge(direction).destination(world_position),
+            })
+            .collect()
+        }
+
+        fn is_loaded(&mut self, world_position: WorldPosition) -> bool {
+            self.loaded.contains(&world_position)
+        }
+    }
+
+    fn position(world_x: i32, x: u32, y: u32) -> Position {
+        Position::new(
+            WorldPosition::new(world_x, 0, 0),
+            LocalPosition::new(x, y, MapLayer::Terrain as u32),
+        )
+    }
+
+    #[test]
+    fn routes_across_maps() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        let mut row = Row::new(&[WorldPosition::ZERO]);
+        let origin = position(0, 10, 5);
+        let destination = position(3, 20, 30);
+        let plan = HierarchicalPathFinder::plan(origin, destination, 0, &mut row, &q_blocks_movement)
+            .expect("the maps are joined");
+
+        // Out the east exit of each map and onto the west exit of the next.
+        let exit = GRID_HEIGHT / 2;
+        let mut expected = Vec::new();
+        for world_x in 0..3 {
+            expected.push(position(world_x, GRID_WIDTH - 1, exit));
+            expected.push(position(world_x + 1, 0, exit));
+        }
+        expected.push(destination);
+        assert_eq!(plan.waypoints().copied().collect::<Vec<_>>(), expected);
+
+        // Only the leg to the first exit is walked out.
+        let path =
+            HierarchicalPathFinder::compute(origin, destination, 0, false, &mut row, &q_blocks_movement)
+                .expect("the maps are joined");
+        assert_eq!(path.first(), Some(&position(0, GRID_WIDTH - 1, exit)));
+        assert!(path.iter().all(|step| step.get_world_position() == WorldPosition::ZERO));
+    }
+
+    #[test]
+    fn gives_up_past_max_searched_maps() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        let mut row = Row::new(&[WorldPosition::ZERO]);
+        let origin = position(0, 10, 5);
+        let within = position(MAX_SEARCHED_MAPS as i32 - 1, 10, 5);
+        let beyond = position(MAX_SEARCHED_MAPS as i32, 10, 5);
+
+        assert!(HierarchicalPathFinder::plan(origin, within, 0, &mut row, &q_blocks_movement).is_some());
+        assert!(HierarchicalPathFinder::plan(origin, beyond, 0, &mut row, &q_blocks_movement).is_none());
+        assert!(
+            HierarchicalPathFinder::compute(origin, beyond, 0, false, &mut row, &q_blocks_movement).is_none()
+        );
+    }
+
+    #[test]
+    fn only_searches_loaded_maps() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        let loaded = [WorldPosition::ZERO, WorldPosition::new(2, 0, 0)];
+        let mut row = Row::new(&loaded);
+        let origin = position(0, 10, 5);
+        let destination = position(4, 20, 30);
+        assert!(
+            HierarchicalPathFinder::compute(origin, destination, 0, false, &mut row, &q_blocks_movement)
+                .is_some()
+        );
+
+        assert!(!row.searched.is_empty());
+        assert!(row.searched.iter().all(|world_position| loaded.contains(world_position)));
+    }
+
+    #[test]
+    fn leg_costs_follow_the_provider() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        let mut row = Row::new(&[WorldPosition::ZERO]);
+        let (from, to) = (position(0, 10, 5), position(0, 20, 8));
+        let estimate = HierarchicalPathFinder::estimate(from, to);
+        let cost =
+            |row: &mut Row| HierarchicalPathFinder::leg_cost(from, to, false, 0, row, &q_blocks_movement);
+        assert_eq!(cost(&mut row), Some(estimate));
+
+        row.cost = 3;
+        assert_eq!(cost(&mut row), Some(3 * estimate));
+    }
+}
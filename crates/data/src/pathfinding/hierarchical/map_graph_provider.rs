@@ -0,0 +1,12 @@
+use crate::prelude::*;
+
+/// What `HierarchicalPathFinder` needs to plan a route across maps
+/// without generating every map along the way.
+pub trait MapGraphProvider: PathProvider {
+    /// Exits of the map at `world_position`.
+    /// Maps which aren't loaded should report where their exits *will* be.
+    fn get_exits(&mut self, world_position: WorldPosition) -> Vec<MapExit>;
+
+    /// Can the map at `world_position` be searched tile by tile without loading it?
+    fn is_loaded(&mut self, world_position: WorldPosition) -> bool;
+}
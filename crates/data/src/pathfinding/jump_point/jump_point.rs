@@ -0,0 +1,404 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use super::super::shared::*;
+use crate::prelude::*;
+
+const CARDINAL_COST: u32 = 10;
+const ORDINAL_COST: u32 = 14;
+
+/// Jumps stop here even on open ground, so a search can't run off
+/// across every map in the world looking for a wall.
+const MAX_JUMP_DISTANCE: u32 = 64;
+
+/// Jump Point Search over an 8 directional grid.
+///
+/// Instead of adding every neighbour to the open list like `AStar`, straight runs of
+/// open ground are skipped over and only the points where a path could turn are
+/// expanded. This only holds while every tile costs the same, so providers without
+/// [`PathProvider::has_uniform_cost`] are handed over to `AStar`.
+pub struct JumpPointSearch;
+
+impl PathAlgorithm for JumpPointSearch {
+    fn compute_path(
+        origin: Position,
+        destination: Position,
+        movement_type: u8,
+        partial_path_on_failure: bool,
+        provider: &mut impl PathProvider,
+        q_blocks_movement: &Query<&BlocksMovement>,
+    ) -> Option<Vec<Position>> {
+        if !provider.has_uniform_cost(movement_type) {
+            return AStar::compute_path(
+                origin,
+                destination,
+                movement_type,
+                partial_path_on_failure,
+                provider,
+                q_blocks_movement,
+            );
+        }
+
+        let mut search = Search {
+            destination,
+            movement_type,
+            provider,
+            q_blocks_movement,
+        };
+
+        // position -> (cost from start, jumped from)
+        let mut nodes: HashMap<Position, (u32, Option<Position>)> = HashMap::new();
+        let mut closed = HashSet::new();
+        let mut open = BinaryHeap::new();
+
+        nodes.insert(origin, (0, None));
+        open.push(JumpNode::new(origin, 0, destination));
+
+        let mut closest = (Self::heuristic(origin, destination), origin);
+        while let Some(JumpNode { position, cost, .. }) = open.pop() {
+            if position == destination {
+                return Some(Self::reconstruct_path(position, &nodes));
+            }
+
+            if !closed.insert(position) {
+                continue;
+            }
+
+            let from_end = Self::heuristic(position, destination);
+            if from_end < closest.0 {
+                closest = (from_end, position);
+            }
+
+            let parent = nodes.get(&position).and_then(|(_, parent)| *parent);
+            for direction in search.successor_directions(position, parent) {
+                let Some((jump_point, steps)) = search.jump(position, direction) else { continue; };
+                if closed.contains(&jump_point) {
+                    continue;
+                }
+
+                let step_cost =
+                    if direction.x != 0 && direction.y != 0 { ORDINAL_COST } else { CARDINAL_COST };
+                let new_cost = cost + steps * step_cost;
+                if nodes.get(&jump_point).map_or(true, |&(current, _)| new_cost < current) {
+                    nodes.insert(jump_point, (new_cost, Some(position)));
+                    open.push(JumpNode::new(jump_point, new_cost, destination));
+                }
+            }
+        }
+
+        // No path found.
+        if partial_path_on_failure {
+            Some(Self::reconstruct_path(closest.1, &nodes))
+        } else {
+            None
+        }
+    }
+}
+
+impl JumpPointSearch {
+    /// Octile distance, scaled the same as the step costs.
+    fn heuristic(from: Position, to: Position) -> u32 {
+        let offset = from.offset_to(to).abs();
+        let (min, max) = (offset.x.min(offset.y) as u32, offset.x.max(offset.y) as u32);
+        CARDINAL_COST * (max - min) + ORDINAL_COST * min
+    }
+
+    /// This will return a path *WITHOUT* the starting point, in the order
+    /// of last point -> first point to match `AStar`.
+    ///
+    /// The jump points are filled back in with every tile in between.
+    fn reconstruct_path(
+        finished: Position,
+        nodes: &HashMap<Position, (u32, Option<Position>)>,
+    ) -> Vec<Position> {
+        let mut ret = Vec::new();
+        let mut current = finished;
+        while let Some(&(_, Some(parent))) = nodes.get(&current) {
+            let direction = current.offset_to(parent).signum();
+            while current != parent {
+                ret.push(current);
+                current += direction;
+            }
+        }
+        ret
+    }
+}
+
+struct Search<'a, 'w, 's, 'q, P: PathProvider> {
+    destination: Position,
+    movement_type: u8,
+    provider: &'a mut P,
+    q_blocks_movement: &'a Query<'w, 's, &'q BlocksMovement>,
+}
+
+impl<'a, 'w, 's, 'q, P: PathProvider> Search<'a, 'w, 's, 'q, P> {
+    fn is_walkable(&mut self, position: Position) -> bool {
+        self.provider.is_walkable(position, self.movement_type, self.q_blocks_movement)
+    }
+
+    /// Directions worth jumping in from `position`, pruning any neighbour
+    /// that could be reached at least as cheaply without going through `position`.
+    fn successor_directions(&mut self, position: Position, parent: Option<Position>) -> Vec<IVec2> {
+        let Some(parent) = parent else {
+            return GridDirection::all().map(|direction| direction.coord()).collect();
+        };
+
+        let direction = parent.offset_to(position).signum();
+        let mut directions = Vec::with_capacity(5);
+        if direction.x != 0 && direction.y != 0 {
+            directions.push(IVec2::new(direction.x, 0));
+            directions.push(IVec2::new(0, direction.y));
+            directions.push(direction);
+        } else {
+            directions.push(direction);
+        }
+        directions.extend(self.forced_directions(position, direction));
+        directions
+    }
+
+    /// Neighbours that only become reachable through `position`
+    /// because a wall next to it blocks the way around.
+    fn forced_directions(&mut self, position: Position, direction: IVec2) -> Vec<IVec2> {
+        let mut forced = Vec::new();
+        if direction.x != 0 && direction.y != 0 {
+            for (blocked, open) in [
+                (
+                    IVec2::new(-direction.x, 0),
+                    IVec2::new(-direction.x, direction.y),
+                ),
+                (
+                    IVec2::new(0, -direction.y),
+                    IVec2::new(direction.x, -direction.y),
+                ),
+            ] {
+                if !self.is_walkable(position + blocked) && self.is_walkable(position + open) {
+                    forced.push(open);
+                }
+            }
+        } else {
+            let side = IVec2::new(direction.y, direction.x);
+            for side in [side, -side] {
+                if !self.is_walkable(position + side) && self.is_walkable(position + side + direction) {
+                    forced.push(side + direction);
+                }
+            }
+        }
+        forced
+    }
+
+    /// Runs from `position` in `direction` until reaching something worth expanding,
+    /// returning it along with the number of steps taken.
+    fn jump(&mut self, position: Position, direction: IVec2) -> Option<(Position, u32)> {
+        let mut current = position;
+        for steps in 1..=MAX_JUMP_DISTANCE {
+            current += direction;
+            if !self.is_walkable(current) {
+                return None;
+            }
+
+            // Stopping next to the destination keeps partial paths as close as `AStar`'s
+            // when the destination itself is blocked.
+            if current.distance(self.destination) <= 1 ||
+                steps == MAX_JUMP_DISTANCE ||
+                !self.forced_directions(current, direction).is_empty()
+            {
+                return Some((current, steps));
+            }
+
+            // A diagonal stops wherever one of its straight runs finds something.
+            if direction.x != 0 &&
+                direction.y != 0 &&
+                (self.jump(current, IVec2::new(direction.x, 0)).is_some() ||
+                    self.jump(current, IVec2::new(0, direction.y)).is_some())
+            {
+                return Some((current, steps));
+            }
+        }
+        None
+    }
+}
+
+/// An open jump point, ordered so the lowest estimated total pops first.
+struct JumpNode {
+    position: Position,
+    cost: u32,
+    cost_total: u32,
+}
+
+impl JumpNode {
+    fn new(position: Position, cost: u32, destination: Position) -> Self {
+        Self {
+            position,
+            cost,
+            cost_total: cost + JumpPointSearch::heuristic(position, destination),
+        }
+    }
+}
+
+impl PartialEq for JumpNode {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for JumpNode {}
+
+impl PartialOrd for JumpNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for JumpNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Prefer the node furthest along on ties, it's closest to the goal.
+        other.cost_total.cmp(&self.cost_total).then(self.cost.cmp(&other.cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u32 = 24;
+
+    /// A single map with `wall_chance`% of its tiles walled off.
+    struct Maze {
+        walls: Grid<bool>,
+        costs: Grid<u32>,
+        uniform: bool,
+    }
+
+    impl Maze {
+        fn new(seed: u64, wall_chance: u32, uniform: bool) -> Self {
+            let mut random = Random::new(seed);
+            let mut walls = Grid::new_default(UVec2::splat(SIZE));
+            let mut costs = Grid::new_copy(UVec2::splat(SIZE), 1);
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    walls.set((x, y), random.prng.max(100) < wall_chance);
+                    if !uniform {
+                        costs.set((x, y), random.prng.range(1..4));
+                    }
+                }
+            }
+            Self {
+                walls,
+                costs,
+                uniform,
+            }
+        }
+    }
+
+    impl PathProvider for Maze {
+        fn is_walkable(&mut self, position: Position, _: u8, _: &Query<&BlocksMovement>) -> bool {
+            position.get_world_position() == WorldPosition::ZERO &&
+                self.walls.get(position.get_local_position().gridpoint()) == Some(&false)
+        }
+
+        fn cost(&mut self, position: Position, _: u8) -> u32 {
+            self.costs.get(position.get_local_position().gridpoint()).copied().unwrap_or(1)
+        }
+
+        fn has_uniform_cost(&mut self, _: u8) -> bool { self.uniform }
+    }
+
+    fn position(x: u32, y: u32) -> Position {
+        Position::new(
+            WorldPosition::ZERO,
+            LocalPosition::new(x, y, MapLayer::Terrain as u32),
+        )
+    }
+
+    /// Length of a path from `origin`, in the same units as the step costs.
+    fn path_length(origin: Position, path: &[Position]) -> u32 {
+        let mut current = origin;
+        path.iter()
+            .rev()
+            .map(|&next| {
+                let offset = current.offset_to(next);
+                current = next;
+                if offset.x != 0 && offset.y != 0 {
+                    ORDINAL_COST
+                } else {
+                    CARDINAL_COST
+                }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn paths_are_as_short_as_astar() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        for wall_chance in [0, 30] {
+            for seed in 0..16 {
+                let mut maze = Maze::new(seed, wall_chance, true);
+                let mut random = Random::new(seed);
+                for _ in 0..8 {
+                    let origin = position(random.prng.max(SIZE), random.prng.max(SIZE));
+                    let destination = position(random.prng.max(SIZE), random.prng.max(SIZE));
+                    let compute = |maze: &mut Maze, jps: bool| {
+                        let path = if jps {
+                            JumpPointSearch::compute_path(
+                                origin,
+                                destination,
+                                0,
+                                false,
+                                maze,
+                                &q_blocks_movement,
+                            )
+                        } else {
+                            AStar::compute_path(origin, destination, 0, false, maze, &q_blocks_movement)
+                        };
+                        path.map(|path| {
+                            // Every step is to a neighbour, ending on the destination.
+                            assert!(path.first().map_or(origin == destination, |&end| end == destination));
+                            path_length(origin, &path)
+                        })
+                    };
+
+                    assert_eq!(
+                        compute(&mut maze, true),
+                        compute(&mut maze, false),
+                        "{}% walls, seed {} from {} to {}",
+                        wall_chance,
+                        seed,
+                        origin,
+                        destination,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn falls_back_to_astar_without_uniform_cost() {
+        let mut world = World::new();
+        let mut state: SystemState<Query<&BlocksMovement>> = SystemState::new(&mut world);
+        let q_blocks_movement = state.get(&world);
+
+        for seed in 0..8 {
+            let mut maze = Maze::new(seed, 20, false);
+            let origin = position(1, 1);
+            let destination = position(SIZE - 2, SIZE - 2);
+            for partial in [false, true] {
+                assert_eq!(
+                    JumpPointSearch::compute_path(
+                        origin,
+                        destination,
+                        0,
+                        partial,
+                        &mut maze,
+                        &q_blocks_movement
+                    ),
+                    AStar::compute_path(
+                        origin,
+                        destination,
+                        0,
+                        partial,
+                        &mut maze,
+                        &q_blocks_movement
+                    ),
+                );
+            }
+        }
+    }
+}
@@ -8,4 +8,8 @@ pub trait PathProvider {
     ) -> bool;
 
     fn cost(&mut self, position: Position, movement_type: u8) -> u32;
+
+    /// Does every walkable tile `cost` the same for this movement type?
+    /// `JumpPointSearch` falls back to `AStar` unless it does.
+    fn has_uniform_cost(&mut self, _movement_type: u8) -> bool { false }
 }
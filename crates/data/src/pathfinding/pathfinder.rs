@@ -3,6 +3,7 @@ use crate::prelude::*;
 pub enum PathFinder {
     Astar,
     Dijkstras,
+    JumpPoint,
 }
 
 impl PathFinder {
@@ -32,6 +33,14 @@ impl PathFinder {
                 provider,
                 q_blocks_movement,
             ),
+            Self::JumpPoint => JumpPointSearch::compute_path(
+                origin,
+                destination,
+                movement_type,
+                partial_path_on_failure,
+                provider,
+                q_blocks_movement,
+            ),
         }
     }
 }
@@ -2,9 +2,11 @@ use crate::prelude::*;
 
 // FIX: PERFORMANCE??
 pub fn entity_in_fov<'w, 's>(
-    map_manager: &mut impl FovProvider,
+    map_manager: &mut MapManager,
     q_blocks_vision: &Query<'w, 's, &'static BlocksVision>,
     fov: &FieldOfView,
+    fov_algorithm: &Fov,
+    facing: &Facing,
     vision: &Vision,
     current_pos: Position,
     destination_pos: Position,
@@ -12,9 +14,9 @@ pub fn entity_in_fov<'w, 's>(
     // // If the player is within the FOV range of the AI, check line of sight
     let distance = current_pos.distance(destination_pos);
     if distance < fov.0 as u32 {
-        let mut visibility_map = VisibilityMap::new();
-        let angle = (destination_pos.gridpoint().angle_to(current_pos.gridpoint()) - 180.0).abs();
-        Fov::ShadowcastDirection(CardinalDirection::from(angle as i32)).compute(
+        let mut visibility_map = VisibilityMap::new(map_manager.get_map_size());
+        fov_algorithm.compute_cone(
+            *facing,
             current_pos,
             vision.0,
             fov.0,
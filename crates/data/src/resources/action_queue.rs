@@ -4,11 +4,18 @@ use crate::prelude::*;
 
 #[derive(Debug, Default, Resource)]
 pub struct ActionQueue {
-    actions: VecDeque<ActionType>,
+    actions: VecDeque<BoxedAction>,
 }
 
 impl ActionQueue {
-    pub fn add_action(&mut self, action: ActionType) { self.actions.push_back(action); }
+    pub fn add_action(&mut self, action: impl Action) { self.actions.push_back(Box::new(action)); }
 
-    pub fn get_action(&mut self) -> Option<ActionType> { self.actions.pop_front() }
+    /// Queue `action` ahead of everything else, such as a multi-turn action carrying on.
+    pub fn add_next(&mut self, action: BoxedAction) { self.actions.push_front(action); }
+
+    pub fn get_action(&mut self) -> Option<BoxedAction> { self.actions.pop_front() }
+
+    pub fn is_empty(&self) -> bool { self.actions.is_empty() }
+
+    pub fn clear(&mut self) { self.actions.clear(); }
 }
@@ -0,0 +1,32 @@
+use crate::prelude::*;
+
+/// How far from the player chase maps are scanned.
+pub const CHASE_MAP_RANGE: f32 = 32.0;
+
+/// `DijkstraMap`s flowing towards the player, one per movement type,
+/// shared by every monster chasing them.
+#[derive(Resource, Default)]
+pub struct ChaseMaps {
+    target: Option<Position>,
+    maps: HashMap<u8, DijkstraMap>,
+}
+
+impl ChaseMaps {
+    pub const fn target(&self) -> Option<Position> { self.target }
+
+    /// Throws away every map if the target has moved.
+    pub fn set_target(&mut self, target: Position) {
+        if self.target != Some(target) {
+            self.target = Some(target);
+            self.maps.clear();
+        }
+    }
+
+    pub fn clear(&mut self) { self.maps.clear(); }
+
+    pub fn get(&self, movement_type: u8) -> Option<&DijkstraMap> { self.maps.get(&movement_type) }
+
+    pub fn contains(&self, movement_type: u8) -> bool { self.maps.contains_key(&movement_type) }
+
+    pub fn insert(&mut self, map: DijkstraMap) { self.maps.insert(map.movement_type(), map); }
+}
@@ -0,0 +1,16 @@
+use crate::prelude::*;
+
+/// The tile under the mouse cursor on the current map, as seen through the map camera.
+///
+/// Only set when the cursor moves onto another tile,
+/// so `Res<CursorPosition>::is_changed()` can be used to skip work.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct CursorPosition {
+    position: Option<Position>,
+}
+
+impl CursorPosition {
+    pub const fn get(&self) -> Option<Position> { self.position }
+
+    pub fn set(&mut self, position: Option<Position>) { self.position = position; }
+}
@@ -0,0 +1,35 @@
+use std::collections::VecDeque;
+
+use crate::prelude::*;
+
+/// Oldest messages are dropped once the log holds this many.
+pub const MAX_MESSAGES: usize = 100;
+
+/// Messages for the player, such as why an action couldn't be performed.
+#[derive(Resource, Default, Debug)]
+pub struct MessageLog {
+    messages: VecDeque<String>,
+    total: usize,
+}
+
+impl MessageLog {
+    pub fn add(&mut self, message: impl Into<String>) {
+        if self.messages.len() >= MAX_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(message.into());
+        self.total += 1;
+    }
+
+    /// Messages from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &String> { self.messages.iter() }
+
+    pub fn last(&self) -> Option<&String> { self.messages.back() }
+
+    pub fn len(&self) -> usize { self.messages.len() }
+
+    pub fn is_empty(&self) -> bool { self.messages.is_empty() }
+
+    /// Number of messages ever added, including any dropped from the log.
+    pub const fn total(&self) -> usize { self.total }
+}
@@ -0,0 +1,20 @@
+use crate::prelude::*;
+
+/// Hands out `PersistentId`s.
+///
+/// This isn't saved, loading catches it up past the highest `PersistentId`
+/// in the loaded world (see `catch_up_persistent_ids`) so ids are never reused.
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+pub struct PersistentIds {
+    next: u64,
+}
+
+impl PersistentIds {
+    pub fn next_id(&mut self) -> PersistentId {
+        self.next += 1;
+        PersistentId(self.next)
+    }
+
+    /// Make sure `id` and everything before it is never handed out.
+    pub fn skip_past(&mut self, id: PersistentId) { self.next = self.next.max(id.0); }
+}
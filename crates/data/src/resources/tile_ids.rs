@@ -31,6 +31,9 @@ pub const TILE_ITEMS_MISSING_ID: TileId = 0;
 pub const TILE_FEATURES_MISSING_ID: TileId = 0;
 pub const TILE_FEATURES_WALL_ID: TileId = 1;
 pub const TILE_FEATURES_WATER_ID: TileId = 2;
+// Auto-tiled terrain, each followed by `AUTO_TILE_VARIANTS` blob variants.
+pub const TILE_FEATURES_WALL_AUTO_START_ID: TileId = 16;
+pub const TILE_FEATURES_WATER_AUTO_START_ID: TileId = 64;
 
 /////////////////////////////////////////////////////////////////////
 /// TERRAIN
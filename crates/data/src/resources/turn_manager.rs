@@ -1,54 +1,68 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
 use crate::prelude::*;
 
+/// When an entity's next turn comes up.
+///
+/// Ordered by turn, then time within the turn, then `tiebreak` so entities
+/// scheduled for the same time keep the order they were scheduled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TurnKey {
+    pub turn_number: u32,
+    pub current_time: u32,
+    tiebreak: i64,
+}
+
+/// Schedules entity turns on an indexed binary heap.
+///
+/// Scheduling, starting and removing a turn are all O(log n), an entity -> slot map
+/// keeps track of where each entity is in the heap.
+///
+/// `Entity` ids don't survive saving and loading, so [`TurnManager::save`] stores each
+/// entity's `PersistentId` and [`TurnManager::load`] matches them to the loaded entities.
 #[derive(Default, Resource)]
 pub struct TurnManager {
     turn_number: u32,
     current_time: u32,
-    entities: IndexList<(u32, u32, Entity)>,
+    /// Min-heap of upcoming turns, the next turn is always at the front.
+    heap: Vec<(TurnKey, Entity)>,
+    /// Index of each scheduled entity in `heap`.
+    slots: HashMap<Entity, usize>,
+    /// Entities on maps which aren't loaded, with the turn they were waiting on.
+    suspended: HashMap<Entity, (u32, u32)>,
+    /// Tiebreaks for the back of a turn count up, for the front they count down.
+    back_tiebreak: i64,
+    front_tiebreak: i64,
 }
 
 impl TurnManager {
-    // This will go into an `impl FromWorld`
-    // FIX: Does it need to?? - Jacob
-    // Yes, as this needs to be serialized,
-    // and Entity is not reflectable. We will
-    // have to match new entity id's to the stored
-    // entities. FromWorld allows us access to all
-    // the resources and entities already generated.
-    // Serialization is going to be a pain! XD
-
-    // pub fn new() -> Self {
-    //     Self {
-    //         turn_number: 0,
-    //         current_time: 0,
-    //         entities: IndexList::new(),
-    //     }
-    // }
+    pub const fn turn_number(&self) -> u32 { self.turn_number }
+
+    pub const fn current_time(&self) -> u32 { self.current_time }
+
+    /// Number of entities waiting for a turn, not counting suspended ones.
+    pub fn len(&self) -> usize { self.heap.len() }
+
+    pub fn is_empty(&self) -> bool { self.heap.is_empty() }
+
+    /// Is `entity` waiting for a turn?
+    pub fn contains(&self, entity: Entity) -> bool { self.slots.contains_key(&entity) }
 
     /// Add entities to the TurnManager when building the map.
+    /// They take their first turn after everyone else waiting on the current time.
     pub fn add_entity(&mut self, entity: Entity) {
-        if let Some((turn_number, current_time, _entity)) = self.entities.get_last() {
-            self.entities.insert_last((*turn_number, *current_time, entity));
-        } else {
-            self.entities.insert_last((self.turn_number, self.current_time, entity));
+        if self.contains(entity) || self.is_suspended(entity) {
+            return;
         }
+
+        self.schedule_back(entity, self.turn_number, self.current_time);
     }
 
     /// Remove an entity when it dies or the map unloads.
     pub fn remove_entity(&mut self, entity: Entity) {
-        let index = self.entities.first_index();
-        let mut found_index = None;
-        while index.is_some() {
-            if let Some((_turn_number, _current_time, current_entity)) = self.entities.get(index) {
-                if *current_entity == entity {
-                    found_index = Some(index);
-                    break;
-                }
-            }
-        }
-
-        if let Some(index) = found_index {
-            self.entities.remove(index);
+        self.suspended.remove(&entity);
+        if let Some(slot) = self.slots.get(&entity).copied() {
+            self.remove_slot(slot);
         }
     }
 
@@ -59,7 +73,9 @@ impl TurnManager {
             self.turn_number = 0;
         }
         self.current_time = 0;
-        self.entities.clear();
+        self.heap.clear();
+        self.slots.clear();
+        self.suspended.clear();
     }
 
     /// This will get the next entity who is ready for a new turn
@@ -73,62 +89,373 @@ impl TurnManager {
     /// if let Ok((PLAYER_QUERY_COMPONENTS)) = q_player_components.get_mut() {[...]}
     /// ```
     pub fn start_entity_turn(&mut self) -> Option<Entity> {
-        if let Some((turn_number, current_time, entity)) = self.entities.remove_first() {
-            // we are at least to turn_number:current_time
-            self.turn_number = turn_number;
-            self.current_time = current_time;
-            return Some(entity);
+        if self.heap.is_empty() {
+            return None;
         }
-        None
+
+        let (key, entity) = self.remove_slot(0);
+        // we are at least to turn_number:current_time
+        self.turn_number = key.turn_number;
+        self.current_time = key.current_time;
+        Some(entity)
     }
 
     /// After the entity performs an action,
     /// call this to re-add the entity to the queue
-    /// time_spent is the amount of time used to perform the action.
+    /// time_spent is the amount of time used to perform the action,
+    /// already sped up or slowed down by the entity's `Speed`.
+    /// Faster entities spend less time, so they come around again sooner.
     pub fn end_entity_turn(&mut self, entity: Entity, time_spent: u32) {
         // shortcut no time spent:
         if time_spent == 0 {
-            self.entities.insert_first((self.turn_number, self.current_time, entity));
+            self.schedule_front(entity, self.turn_number, self.current_time);
             return;
         }
 
-        let mut next_turn = self.turn_number;
-        let mut next_time = self.current_time + time_spent;
+        let next_time = self.current_time + time_spent;
+        self.schedule_back(
+            entity,
+            self.turn_number + next_time / TURN_TIME,
+            next_time % TURN_TIME,
+        );
+    }
 
-        loop {
-            if next_time < TURN_TIME {
-                break;
+    /// The next `count` entities to take a turn, in order, without starting any of them.
+    pub fn peek_order(&self, count: usize) -> Vec<Entity> {
+        // Only the children of entries already taken can be next, so this
+        // walks the heap from the root instead of sorting all of it.
+        let mut order = Vec::with_capacity(count.min(self.heap.len()));
+        let mut open = BinaryHeap::new();
+        if !self.heap.is_empty() {
+            open.push(Reverse((self.heap[0].0, 0)));
+        }
+
+        while order.len() < count {
+            let Some(Reverse((_, slot))) = open.pop() else { break; };
+            order.push(self.heap[slot].1);
+            for child in [slot * 2 + 1, slot * 2 + 2] {
+                if let Some((key, _)) = self.heap.get(child) {
+                    open.push(Reverse((*key, child)));
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Take an entity out of the queue while its map isn't loaded,
+    /// remembering the turn it was waiting on.
+    ///
+    /// Returns `false` if the entity wasn't waiting for a turn.
+    pub fn suspend_entity(&mut self, entity: Entity) -> bool {
+        let Some(slot) = self.slots.get(&entity).copied() else { return false; };
+
+        let (key, _) = self.remove_slot(slot);
+        self.suspended.insert(entity, (key.turn_number, key.current_time));
+        true
+    }
+
+    /// Put a suspended entity back in the queue once its map is loaded again.
+    ///
+    /// It keeps its place if that is still to come, otherwise it waits for the
+    /// current time, so it doesn't get a burst of turns for the time it was away.
+    pub fn resume_entity(&mut self, entity: Entity) -> bool {
+        let Some((turn_number, current_time)) = self.suspended.remove(&entity) else { return false; };
+
+        let (turn_number, current_time) =
+            (turn_number, current_time).max((self.turn_number, self.current_time));
+        self.schedule_back(entity, turn_number, current_time);
+        true
+    }
+
+    pub fn is_suspended(&self, entity: Entity) -> bool { self.suspended.contains_key(&entity) }
+
+    /// Every entity taken out of the queue by `suspend_entity()`.
+    pub fn suspended(&self) -> impl Iterator<Item = Entity> + '_ { self.suspended.keys().copied() }
+}
+
+// Saving and loading
+impl TurnManager {
+    /// Everything needed to rebuild this `TurnManager` once the world is loaded again.
+    ///
+    /// Entities without a `PersistentId` can't be found after loading,
+    /// so their turns are left out.
+    pub fn save(&self, world: &World) -> TurnManagerSave {
+        let persistent_id = |entity: Entity| world.get::<PersistentId>(entity).copied();
+
+        let mut heap = self.heap.clone();
+        heap.sort_unstable_by_key(|(key, _)| *key);
+        let waiting = heap.into_iter().filter_map(|(key, entity)| {
+            Some(SavedTurn {
+                id: persistent_id(entity)?,
+                turn_number: key.turn_number,
+                current_time: key.current_time,
+                suspended: false,
+            })
+        });
+
+        let mut suspended: Vec<SavedTurn> = self
+            .suspended
+            .iter()
+            .filter_map(|(&entity, &(turn_number, current_time))| {
+                Some(SavedTurn {
+                    id: persistent_id(entity)?,
+                    turn_number,
+                    current_time,
+                    suspended: true,
+                })
+            })
+            .collect();
+        suspended.sort_unstable_by_key(|turn| (turn.turn_number, turn.current_time, turn.id));
+
+        TurnManagerSave {
+            turn_number: self.turn_number,
+            current_time: self.current_time,
+            turns: waiting.chain(suspended).collect(),
+        }
+    }
+
+    /// Rebuild a saved `TurnManager`, finding each entity by its `PersistentId`.
+    ///
+    /// Every entity keeps its exact turn,
+    /// and entities waiting on the same time keep their order.
+    pub fn load(save: &TurnManagerSave, world: &mut World) -> Self {
+        let entities: HashMap<PersistentId, Entity> =
+            world.query::<(Entity, &PersistentId)>().iter(world).map(|(entity, &id)| (id, entity)).collect();
+
+        let mut turn_manager = Self {
+            turn_number: save.turn_number,
+            current_time: save.current_time,
+            ..Default::default()
+        };
+
+        for turn in &save.turns {
+            let Some(&entity) = entities.get(&turn.id) else {
+                warn!("No entity with {:?} was loaded, dropping its turn", turn.id);
+                continue;
+            };
+
+            if turn.suspended {
+                turn_manager.suspended.insert(entity, (turn.turn_number, turn.current_time));
+            } else {
+                turn_manager.schedule_back(entity, turn.turn_number, turn.current_time);
             }
-            next_turn += 1;
-            next_time -= TURN_TIME;
         }
 
-        if let Some(index) = self.get_index_after_time(next_turn, next_time) {
-            self.entities.insert_before(index, (next_turn, next_time, entity));
-        } else {
-            self.entities.insert_last((next_turn, next_time, entity));
+        turn_manager
+    }
+}
+
+/// A turn waiting in a saved `TurnManager`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SavedTurn {
+    pub id: PersistentId,
+    pub turn_number: u32,
+    pub current_time: u32,
+    pub suspended: bool,
+}
+
+/// What gets saved of a `TurnManager`, with entities swapped for their `PersistentId`s.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TurnManagerSave {
+    pub turn_number: u32,
+    pub current_time: u32,
+    /// Waiting turns in the order they will be taken, then suspended turns.
+    pub turns: Vec<SavedTurn>,
+}
+
+// Heap internals
+impl TurnManager {
+    fn schedule_back(&mut self, entity: Entity, turn_number: u32, current_time: u32) {
+        self.back_tiebreak += 1;
+        self.push(
+            TurnKey {
+                turn_number,
+                current_time,
+                tiebreak: self.back_tiebreak,
+            },
+            entity,
+        );
+    }
+
+    fn schedule_front(&mut self, entity: Entity, turn_number: u32, current_time: u32) {
+        self.front_tiebreak -= 1;
+        self.push(
+            TurnKey {
+                turn_number,
+                current_time,
+                tiebreak: self.front_tiebreak,
+            },
+            entity,
+        );
+    }
+
+    fn push(&mut self, key: TurnKey, entity: Entity) {
+        // An entity only ever has one turn waiting.
+        self.remove_entity(entity);
+
+        let slot = self.heap.len();
+        self.heap.push((key, entity));
+        self.slots.insert(entity, slot);
+        self.sift_up(slot);
+    }
+
+    fn remove_slot(&mut self, slot: usize) -> (TurnKey, Entity) {
+        let last = self.heap.len() - 1;
+        self.swap(slot, last);
+        let removed = self.heap.pop().unwrap();
+        self.slots.remove(&removed.1);
+
+        if slot < self.heap.len() {
+            self.sift_down(slot);
+            self.sift_up(slot);
+        }
+        removed
+    }
+
+    fn sift_up(&mut self, mut slot: usize) {
+        while slot > 0 {
+            let parent = (slot - 1) / 2;
+            if self.heap[slot].0 >= self.heap[parent].0 {
+                break;
+            }
+            self.swap(slot, parent);
+            slot = parent;
         }
     }
 
-    fn get_index_after_time(&self, turn_number: u32, time: u32) -> Option<Index> {
-        let mut index = self.entities.first_index();
-        while index.is_some() {
-            if let Some((current_turn_number, current_time, _entity)) = self.entities.get(index) {
-                if *current_turn_number > turn_number ||
-                    (*current_turn_number == turn_number && *current_time > time)
-                {
-                    return Some(index);
+    fn sift_down(&mut self, mut slot: usize) {
+        loop {
+            let mut smallest = slot;
+            for child in [slot * 2 + 1, slot * 2 + 2] {
+                if child < self.heap.len() && self.heap[child].0 < self.heap[smallest].0 {
+                    smallest = child;
                 }
             }
-            index = self.entities.next_index(index);
+
+            if smallest == slot {
+                break;
+            }
+            self.swap(slot, smallest);
+            slot = smallest;
         }
-        None
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.slots.insert(self.heap[a].1, a);
+        self.slots.insert(self.heap[b].1, b);
     }
 }
 
-// impl FromWorld for TurnManager {
-//     fn from_world(_world: &mut World) -> Self {
-//         println!("TurnManager::from_world");
-//         Self::new()
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(id: u32) -> Entity { Entity::from_raw(id) }
+
+    #[test]
+    fn turns_come_around_by_time_spent() {
+        let mut turn_manager = TurnManager::default();
+        for id in 0..3 {
+            turn_manager.add_entity(entity(id));
+        }
+        assert_eq!(
+            turn_manager.peek_order(3),
+            (0..3).map(entity).collect::<Vec<_>>()
+        );
+
+        // A fast entity gets two turns in before the others' next ones.
+        let fast = turn_manager.start_entity_turn().unwrap();
+        turn_manager.end_entity_turn(fast, TURN_TIME / 2);
+        for _ in 0..2 {
+            let entity = turn_manager.start_entity_turn().unwrap();
+            turn_manager.end_entity_turn(entity, TURN_TIME);
+        }
+        assert_eq!(
+            turn_manager.peek_order(3),
+            (0..3).map(entity).collect::<Vec<_>>()
+        );
+
+        // Spending no time goes straight back to the front.
+        let first = turn_manager.start_entity_turn().unwrap();
+        turn_manager.end_entity_turn(first, 0);
+        assert_eq!(turn_manager.start_entity_turn(), Some(first));
+        assert_eq!(
+            (turn_manager.turn_number(), turn_manager.current_time()),
+            (0, TURN_TIME / 2)
+        );
+    }
+
+    #[test]
+    fn remove_and_suspend_any_entity() {
+        let mut turn_manager = TurnManager::default();
+        for id in 0..8 {
+            turn_manager.add_entity(entity(id));
+        }
+
+        turn_manager.remove_entity(entity(5));
+        assert!(turn_manager.suspend_entity(entity(2)));
+        assert_eq!(turn_manager.len(), 6);
+        assert!(!turn_manager.contains(entity(5)));
+        assert!(turn_manager.is_suspended(entity(2)));
+
+        for _ in 0..6 {
+            let entity = turn_manager.start_entity_turn().unwrap();
+            turn_manager.end_entity_turn(entity, TURN_TIME);
+        }
+
+        // Resumed entities wait for the current time rather than catching up.
+        let next = turn_manager.start_entity_turn().unwrap();
+        assert!(turn_manager.resume_entity(entity(2)));
+        let order = turn_manager.peek_order(8);
+        assert_eq!(order.len(), 6);
+        assert!(!order.contains(&next));
+        assert_eq!(order.last(), Some(&entity(2)));
+    }
+
+    #[test]
+    fn save_and_load_keeps_every_turn() {
+        let mut world = World::new();
+        let mut turn_manager = TurnManager::default();
+        let entities: Vec<Entity> = (1..=4).map(|id| world.spawn(PersistentId(id)).id()).collect();
+        for &entity in &entities {
+            turn_manager.add_entity(entity);
+        }
+        // Can't be found again after loading.
+        turn_manager.add_entity(world.spawn_empty().id());
+
+        for time_spent in [TURN_TIME, TURN_TIME / 2, 0, TURN_TIME * 2] {
+            let entity = turn_manager.start_entity_turn().unwrap();
+            turn_manager.end_entity_turn(entity, time_spent);
+        }
+        turn_manager.suspend_entity(entities[3]);
+
+        let save = turn_manager.save(&world);
+        assert_eq!(save.turns.len(), 4);
+        let save: TurnManagerSave = ron::from_str(&ron::to_string(&save).unwrap()).unwrap();
+
+        // Spawned in a different order, so none of the entities match.
+        let mut loaded_world = World::new();
+        loaded_world.spawn_empty();
+        for id in (1..=4).rev() {
+            loaded_world.spawn(PersistentId(id));
+        }
+
+        let loaded = TurnManager::load(&save, &mut loaded_world);
+        assert_eq!(loaded.save(&loaded_world), save);
+        assert_eq!(
+            (loaded.turn_number(), loaded.current_time()),
+            (turn_manager.turn_number(), turn_manager.current_time())
+        );
+
+        let ids = |turn_manager: &TurnManager, world: &World| -> Vec<PersistentId> {
+            turn_manager
+                .peek_order(8)
+                .into_iter()
+                .filter_map(|entity| world.get::<PersistentId>(entity).copied())
+                .collect()
+        };
+        assert_eq!(ids(&loaded, &loaded_world), ids(&turn_manager, &world));
+    }
+}
@@ -37,6 +37,8 @@ impl DirectionBitmap {
 
     pub const fn has(self, direction: Direction) -> bool { self.raw & (1 << direction as usize) != 0 }
 
+    pub const fn contains(self, direction: GridDirection) -> bool { self.raw & direction.bitmap_raw() != 0 }
+
     pub const fn is_empty(self) -> bool { self.raw == NO_DIRECTIONS_BITMAP_RAW }
 
     pub const fn is_full(self) -> bool { self.raw == ALL_DIRECTIONS_BITMAP_RAW }
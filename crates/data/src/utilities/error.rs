@@ -11,6 +11,8 @@ pub enum AtrlError {
 
     #[error("Invalid world_position {{ {}, {}, {} }}", .0.x, .0.y, .0.z)]
     InvalidWorldPosition(IVec3),
+    #[error("Invalid map sample: {}", .0)]
+    InvalidSample(String),
 
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -7,6 +7,7 @@ pub const DIAGONAL_COST: f32 = SQRT_2;
 
 /// Enumeration of available 2D Distance algorithms
 #[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DistanceAlg {
     /// Use the Pythagoras algorithm for determining distance - sqrt(A^2 + B^2)
     Pythagoras,
@@ -17,7 +17,8 @@ impl<T: StateNext> Plugin for AIPlugin<T> {
     fn build(&self, app: &mut App) {
         self.setup_ai_stages(app);
 
-        app.add_plugin(BigBrainPlugin)
+        app.init_resource::<ChaseMaps>()
+            .add_plugin(BigBrainPlugin)
             // Scoring Systems
             .add_system_set_to_stage(
                 BigBrainStage::Scorers,
@@ -26,10 +27,20 @@ impl<T: StateNext> Plugin for AIPlugin<T> {
                     .with_system(can_see_player)
                     .into(),
             )
+            // Shared Dijkstra Maps
+            .add_system_set_to_stage(
+                AtrlStage::AIThinking,
+                ConditionSet::new()
+                    .label("update_chase_maps")
+                    .run_in_state(self.state_running)
+                    .with_system(update_chase_maps)
+                    .into(),
+            )
             // Action Systems
             .add_system_set_to_stage(
                 AtrlStage::AIThinking,
                 ConditionSet::new()
+                    .after("update_chase_maps")
                     .run_in_state(self.state_running)
                     .with_system(wander_action)
                     .with_system(chase_action)
@@ -58,7 +58,7 @@ pub fn attack_action(
             ActionState::Init | ActionState::Requested => {
                 info!("{} gonna start attacking!", name);
                 *action_state = Executing;
-                ai_component.preferred_action = Some(ActionType::Attack(player_position));
+                ai_component.preferred_action = Some(Box::new(AttackAction::new(player_position)));
 
                 if let Ok(mut target_visualizer) = target_q.get_mut(*actor) {
                     target_visualizer.set_color(Color::RED);
@@ -71,10 +71,10 @@ pub fn attack_action(
         if in_attack_range(*ai_position, player_position) {
             println!("{} is in attack range!", name);
             // *action_state = ActionState::Success;
-            ai_component.preferred_action = Some(ActionType::Attack(player_position));
+            ai_component.preferred_action = Some(Box::new(AttackAction::new(player_position)));
         } else {
             *action_state = ActionState::Failure;
-            ai_component.preferred_action = Some(ActionType::Movement(player_position));
+            ai_component.preferred_action = Some(Box::new(MoveAction::new(player_position)));
         }
     }
 }
@@ -10,26 +10,17 @@ pub struct ChaseActor {
     last_seen_pt: Option<Position>,
 }
 
-pub fn chase_action<'w, 's>(
+pub fn chase_action(
     mut commands: Commands,
     mut map_manager: MapManager,
+    chase_maps: Res<ChaseMaps>,
     player_entity: Res<PlayerEntity>,
     mut target_q: Query<&mut TargetVisualizer>,
     mut action_q: Query<(&Actor, &mut ActionState, &mut ChaseActor)>,
 
-    mut blocking_set: ParamSet<(
-        Query<'w, 's, &'static BlocksVision>,
-        Query<'w, 's, &'static BlocksMovement>,
-    )>,
-
-    mut mobs_q: Query<(
-        &Position,
-        &FieldOfView,
-        &Movement,
-        &Vision,
-        &Name,
-        &mut AIComponent,
-    )>,
+    q_blocks_movement: Query<&BlocksMovement>,
+
+    mut mobs_q: Query<(&Position, &Viewshed, &Movement, &Name, &mut AIComponent)>,
 ) {
     use ActionState::*;
 
@@ -39,7 +30,7 @@ pub fn chase_action<'w, 's>(
     };
 
     for (Actor(actor), mut action_state, mut chase) in action_q.iter_mut() {
-        let Ok((&ai_position, fov, movement,vision, name, mut ai_component)) =
+        let Ok((&ai_position, viewshed, movement, name, mut ai_component)) =
             mobs_q.get_mut(*actor) else {
                 info!("Actor must have required components");
                 continue;
@@ -79,7 +70,7 @@ pub fn chase_action<'w, 's>(
 
                 chase.generated_path = false;
                 chase.last_seen_pt = Some(player_position);
-                ai_component.preferred_action = Some(ActionType::Movement(player_position));
+                ai_component.preferred_action = Some(Box::new(MoveAction::new(player_position)));
 
                 if let Ok(mut target_visualizer) = target_q.get_mut(*actor) {
                     target_visualizer.set_color(Color::RED);
@@ -91,14 +82,7 @@ pub fn chase_action<'w, 's>(
 
         info!("{} executing chase!", name);
 
-        let position = if entity_in_fov(
-            &mut map_manager,
-            &blocking_set.p0(),
-            fov,
-            vision,
-            ai_position,
-            player_position,
-        ) {
+        let position = if viewshed.is_visible(player_position) {
             if in_attack_range(ai_position, player_position) {
                 *action_state = Success;
                 continue;
@@ -106,11 +90,16 @@ pub fn chase_action<'w, 's>(
 
             chase.last_seen_pt = Some(player_position);
             chase.generated_path = false;
-            player_position
+
+            // Follow the shared chase map rather than path finding for every monster.
+            chase_maps
+                .get(movement.0)
+                .and_then(|map| map.downhill(ai_position, &mut map_manager, &q_blocks_movement))
+                .unwrap_or(player_position)
         } else {
             let Some(last_seen) = chase.last_seen_pt else {
                         error!("Executing chase with no target.");
-                        ai_component.preferred_action = Some(ActionType::Wait);
+                        ai_component.preferred_action = Some(Box::new(WaitAction));
                         continue;
                     };
 
@@ -130,7 +119,7 @@ pub fn chase_action<'w, 's>(
                     last_seen,
                     movement.0,
                     &mut map_manager,
-                    &blocking_set.p1(),
+                    &q_blocks_movement,
                 );
                 let point = path.first().unwrap_or(&last_seen);
 
@@ -142,7 +131,7 @@ pub fn chase_action<'w, 's>(
             }
         };
 
-        ai_component.preferred_action = Some(ActionType::Movement(position));
+        ai_component.preferred_action = Some(Box::new(MoveAction::new(position)));
     }
 }
 
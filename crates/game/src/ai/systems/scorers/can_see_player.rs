@@ -13,14 +13,13 @@ impl Default for CanSeePlayer {
     fn default() -> Self { Self { score_if_true: 1.0 } }
 }
 
-pub fn can_see_player<'w, 's>(
-    mut map_manager: MapManager,
-    mobs_q: Query<(&Position, &FieldOfView, &Vision)>,
+pub fn can_see_player(
+    q_position: Query<&Position>,
+    q_viewshed: Query<&Viewshed>,
     player_entity: Res<PlayerEntity>,
     mut query: Query<(&Actor, &mut Score, &CanSeePlayer)>,
-    q_blocks_vision: Query<'w, 's, &'static BlocksVision>,
 ) {
-    let Ok((player_position, ..)) = mobs_q.get(player_entity.current()) else {
+    let Ok(player_position) = q_position.get(player_entity.current()) else {
         error!("No player!");
         return;
     };
@@ -31,15 +30,8 @@ pub fn can_see_player<'w, 's>(
         }
         let mut current_score = 0.0;
 
-        if let Ok((ai_position, fov, vision)) = mobs_q.get(*actor) {
-            if entity_in_fov(
-                &mut map_manager,
-                &q_blocks_vision,
-                fov,
-                vision,
-                *ai_position,
-                *player_position,
-            ) {
+        if let Ok(viewshed) = q_viewshed.get(*actor) {
+            if viewshed.is_visible(*player_position) {
                 current_score = can_see_player.score_if_true;
             }
         }
@@ -0,0 +1,29 @@
+use crate::prelude::*;
+
+pub fn update_chase_maps(
+    mut map_manager: MapManager,
+    mut chase_maps: ResMut<ChaseMaps>,
+    player_entity: Res<PlayerEntity>,
+    q_position: Query<&Position>,
+    q_movement: Query<&Movement, With<AIComponent>>,
+    q_blocks_movement: Query<&BlocksMovement>,
+) {
+    let Ok(&player_position) = q_position.get(player_entity.current()) else { return; };
+
+    chase_maps.set_target(player_position);
+
+    // Only scan for movement types an AI actually uses.
+    for movement in q_movement.iter() {
+        if chase_maps.contains(movement.0) {
+            continue;
+        }
+
+        chase_maps.insert(DijkstraMap::compute(
+            [(player_position, 0.0)],
+            movement.0,
+            CHASE_MAP_RANGE,
+            &mut map_manager,
+            &q_blocks_movement,
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::prelude::*;
+
+/// Keeps `PersistentIds` past every `PersistentId` in the world.
+///
+/// Loading a world brings its entities' ids along, so this runs after any load
+/// to make sure none of them are handed out again.
+pub fn catch_up_persistent_ids(
+    mut persistent_ids: ResMut<PersistentIds>,
+    q_added_ids: Query<&PersistentId, Added<PersistentId>>,
+) {
+    if let Some(&highest) = q_added_ids.iter().max() {
+        persistent_ids.skip_past(highest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_past_loaded_ids() {
+        let mut world = World::new();
+        world.init_resource::<PersistentIds>();
+        for id in [7, 3] {
+            world.spawn(PersistentId(id));
+        }
+
+        let mut stage = SystemStage::single(catch_up_persistent_ids);
+        stage.run(&mut world);
+        assert_eq!(
+            world.resource_mut::<PersistentIds>().next_id(),
+            PersistentId(8)
+        );
+
+        // A lower id loaded later doesn't wind it back.
+        world.spawn(PersistentId(2));
+        stage.run(&mut world);
+        assert_eq!(
+            world.resource_mut::<PersistentIds>().next_id(),
+            PersistentId(9)
+        );
+    }
+}
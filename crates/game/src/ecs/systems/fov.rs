@@ -1,25 +1,12 @@
 use crate::prelude::*;
 
-pub fn fov<'w, 's>(
+pub fn fov(
     mut map_manager: MapManager,
     player_entity: Res<PlayerEntity>,
-    q_vision: Query<(&Position, &FieldOfView, &Vision)>,
-    q_blocks_vision: Query<'w, 's, &'static BlocksVision>,
+    q_viewshed: Query<&Viewshed, Changed<Viewshed>>,
 ) {
-    let Ok((player_position, fov, vision_component)) = q_vision.get(player_entity.current()) else {
-        error!("No player");
-        return;
-    };
+    // Only changes when the player's viewshed is recomputed.
+    let Ok(viewshed) = q_viewshed.get(player_entity.current()) else { return; };
 
-    let mut visibility_map = VisibilityMap::new();
-    Fov::Shadowcast.compute(
-        *player_position,
-        vision_component.0,
-        fov.0,
-        &mut map_manager,
-        &q_blocks_vision,
-        &mut visibility_map,
-    );
-
-    map_manager.set_visibility(visibility_map);
+    map_manager.set_visibility(viewshed.visibility_map().clone());
 }
@@ -0,0 +1,74 @@
+use crate::prelude::*;
+
+pub fn invalidate_paths(
+    map_manager: MapManager,
+    mut q_paths: Query<(Entity, &mut PathCache)>,
+    q_moved_blockers: Query<
+        (Entity, &Position),
+        (
+            With<BlocksMovement>,
+            Or<(Changed<Position>, Changed<BlocksMovement>)>,
+        ),
+    >,
+    removed_blockers: RemovedComponents<BlocksMovement>,
+) {
+    // We don't know where removed blockers were, so every route has to be planned again.
+    // The same goes for a newly loaded map, its exits may not be where routes expected.
+    if removed_blockers.iter().next().is_some() || map_manager.has_newly_loaded_maps() {
+        q_paths.for_each_mut(|(_, mut path_cache)| path_cache.invalidate());
+        return;
+    }
+
+    // Blockers (including doors closing) and terrain changing on a route block it.
+    // An actor's own movement never blocks its route.
+    let changes: Vec<(Option<Entity>, Position)> = q_moved_blockers
+        .iter()
+        .map(|(entity, &position)| (Some(entity), position))
+        .chain(map_manager.get_terrain_changes().iter().map(|&position| (None, position)))
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
+
+    q_paths.for_each_mut(|(entity, mut path_cache)| {
+        if changes
+            .iter()
+            .any(|&(blocker, position)| blocker != Some(entity) && path_cache.passes_through(position))
+        {
+            path_cache.invalidate();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidates_routes_a_blocker_lands_on() {
+        let mut world = test_world(UVec2::new(16, 16));
+
+        let mut path_cache = PathCache::new();
+        path_cache.set(test_position(5, 2), vec![
+            test_position(5, 2),
+            test_position(4, 2),
+            test_position(3, 2),
+        ]);
+        let actor = world.spawn((test_position(2, 2), BlocksMovement::default(), path_cache)).id();
+        let blocker = world.spawn((test_position(8, 8), BlocksMovement::default())).id();
+
+        // Both were just spawned, but neither is on the route.
+        let mut stage = SystemStage::single(invalidate_paths);
+        stage.run(&mut world);
+        assert!(!world.get::<PathCache>(actor).unwrap().is_empty());
+
+        // The actor moving along its own route.
+        world.get_mut::<Position>(actor).unwrap().set_x(3);
+        stage.run(&mut world);
+        assert!(!world.get::<PathCache>(actor).unwrap().is_empty());
+
+        *world.get_mut::<Position>(blocker).unwrap() = test_position(4, 2);
+        stage.run(&mut world);
+        assert!(world.get::<PathCache>(actor).unwrap().is_empty());
+    }
+}
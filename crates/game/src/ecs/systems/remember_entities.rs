@@ -0,0 +1,41 @@
+use crate::prelude::*;
+
+/// Hides actors out of the player's view, leaving a ghost where they were last seen.
+pub fn remember_entities(
+    mut map_manager: MapManager,
+    player_entity: Res<PlayerEntity>,
+    mut q_entities: Query<
+        (
+            Entity,
+            &Position,
+            &TextureAtlasSprite,
+            &Handle<TextureAtlas>,
+            &mut Visibility,
+        ),
+        (With<Mob>, Without<Ghost>),
+    >,
+) {
+    let current_world_position = map_manager.get_current_world_position();
+
+    // Whatever is in view now replaces what we remembered there.
+    map_manager.forget_visible_entities();
+
+    for (entity, &position, sprite, texture_atlas, mut visibility) in q_entities.iter_mut() {
+        if entity == player_entity.current() {
+            visibility.is_visible = true;
+            continue;
+        }
+
+        let is_visible = position.get_world_position() == current_world_position &&
+            map_manager.get_visible_tiles(current_world_position).map_or(false, |visible_tiles| {
+                visible_tiles.get(position.gridpoint()).map_or(false, |visible| *visible)
+            });
+
+        visibility.is_visible = is_visible;
+        if is_visible {
+            map_manager.remember_entity(entity, position, sprite, texture_atlas);
+        }
+    }
+
+    map_manager.spawn_ghosts();
+}
@@ -0,0 +1,46 @@
+use crate::prelude::*;
+
+pub fn update_lights<'w, 's>(
+    mut map_manager: MapManager,
+    q_lights: Query<(&Position, &LightSource)>,
+    q_changed_lights: Query<
+        (),
+        (
+            With<LightSource>,
+            Or<(Changed<Position>, Changed<LightSource>)>,
+        ),
+    >,
+    q_moved_blockers: Query<
+        (),
+        (
+            With<BlocksVision>,
+            Or<(Changed<Position>, Changed<BlocksVision>)>,
+        ),
+    >,
+    removed_lights: RemovedComponents<LightSource>,
+    removed_blockers: RemovedComponents<BlocksVision>,
+    mut q_viewsheds: Query<(&Vision, &mut Viewshed)>,
+    q_blocks_vision: Query<'w, 's, &'static BlocksVision>,
+) {
+    if q_changed_lights.is_empty() &&
+        q_moved_blockers.is_empty() &&
+        removed_lights.iter().next().is_none() &&
+        removed_blockers.iter().next().is_none() &&
+        !map_manager.has_terrain_changes() &&
+        !map_manager.has_newly_loaded_maps()
+    {
+        return;
+    }
+
+    map_manager.clear_lights();
+    for (&position, light_source) in q_lights.iter() {
+        map_manager.add_light(position, light_source, &q_blocks_vision);
+    }
+
+    // Anyone who needs light to see has to look again.
+    q_viewsheds.for_each_mut(|(vision, mut viewshed)| {
+        if VisionType::needs_light(vision.0) {
+            viewshed.mark_dirty();
+        }
+    });
+}
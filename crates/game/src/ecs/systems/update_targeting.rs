@@ -6,8 +6,8 @@ pub fn update_targeting(
     mut target_q: Query<(&Position, &AIComponent, &mut TargetVisualizer), Changed<AIComponent>>,
 ) {
     for (ai_position, ai_component, mut target_visualizer) in target_q.iter_mut() {
-        if ai_component.preferred_action.is_some() {
-            if let Some(ActionType::Movement(pos) | ActionType::Attack(pos)) = ai_component.preferred_action {
+        if let Some(action) = ai_component.preferred_action.as_ref() {
+            if let Some(pos) = action.target() {
                 target_visualizer.update(
                     &mut commands,
                     &tilesets,
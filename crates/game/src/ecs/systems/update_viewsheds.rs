@@ -0,0 +1,123 @@
+use crate::prelude::*;
+
+pub fn update_viewsheds<'w, 's>(
+    mut map_manager: MapManager,
+    mut q_viewers: Query<(
+        &Position,
+        &FieldOfView,
+        &Fov,
+        &Facing,
+        &Vision,
+        &mut Viewshed,
+    )>,
+    q_moved_blockers: Query<
+        &Position,
+        (
+            With<BlocksVision>,
+            Or<(Changed<Position>, Changed<BlocksVision>)>,
+        ),
+    >,
+    removed_blockers: RemovedComponents<BlocksVision>,
+    q_blocks_vision: Query<'w, 's, &'static BlocksVision>,
+) {
+    // We don't know where removed blockers were, so everyone has to look again.
+    // Same for a newly loaded map, anyone near its edge was looking at a wall.
+    if removed_blockers.iter().next().is_some() || map_manager.has_newly_loaded_maps() {
+        q_viewers.for_each_mut(|(.., mut viewshed)| viewshed.mark_dirty());
+    }
+
+    // Anything that blocks vision changing near a viewer invalidates its viewshed.
+    // Blockers only move one tile per turn, so +1 covers the tile they left.
+    let changes: Vec<Position> =
+        q_moved_blockers.iter().copied().chain(map_manager.take_terrain_changes()).collect();
+    if !changes.is_empty() {
+        q_viewers.for_each_mut(|(_, fov, .., mut viewshed)| {
+            let range = fov.0 as u32 + 1;
+            if changes.iter().any(|&position| viewshed.is_affected_by(position, range)) {
+                viewshed.mark_dirty();
+            }
+        });
+    }
+
+    for (&position, fov, fov_algorithm, &facing, vision, mut viewshed) in q_viewers.iter_mut() {
+        if !viewshed.needs_update(position, facing) {
+            continue;
+        }
+
+        let mut visibility_map = VisibilityMap::new(map_manager.get_map_size());
+        fov_algorithm.compute_cone(
+            facing,
+            position,
+            vision.0,
+            fov.0,
+            &mut map_manager,
+            &q_blocks_vision,
+            &mut visibility_map,
+        );
+        map_manager.remove_unlit(position, vision.0, &mut visibility_map);
+        viewshed.set(position, facing, visibility_map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A world with an empty map and a viewer at (8, 8) which can see 4 tiles.
+    fn viewer_world() -> (World, Entity) {
+        let mut world = test_world(UVec2::new(32, 32));
+        let viewer = world
+            .spawn((
+                test_position(8, 8),
+                FieldOfView(4),
+                Fov::default(),
+                Facing::default(),
+                // Doesn't need light, so everything in range is visible.
+                Vision(VisionType::Infared.as_u8()),
+                Viewshed::new(),
+            ))
+            .id();
+        (world, viewer)
+    }
+
+    /// Runs `update_viewsheds` and reports whether the viewer's viewshed was recomputed.
+    fn recomputed(world: &mut World, stage: &mut SystemStage, viewer: Entity) -> bool {
+        // An empty viewshed at the current origin is only filled in again by a recompute.
+        let origin = *world.get::<Position>(viewer).unwrap();
+        let facing = *world.get::<Facing>(viewer).unwrap();
+        world.get_mut::<Viewshed>(viewer).unwrap().set(origin, facing, VisibilityMap::default());
+
+        stage.run(world);
+        world.get::<Viewshed>(viewer).unwrap().visibility_map().iter().next().is_some()
+    }
+
+    #[test]
+    fn recomputes_only_for_nearby_changes() {
+        let (mut world, viewer) = viewer_world();
+        let mut stage = SystemStage::single(update_viewsheds);
+        stage.run(&mut world);
+        assert!(world.get::<Viewshed>(viewer).unwrap().is_visible(test_position(10, 8)));
+
+        // Nothing changed.
+        assert!(!recomputed(&mut world, &mut stage, viewer));
+
+        // A blocker out of range.
+        world.spawn((test_position(20, 20), BlocksVision::default()));
+        assert!(!recomputed(&mut world, &mut stage, viewer));
+
+        // A blocker within range.
+        world.spawn((test_position(9, 9), BlocksVision::default()));
+        assert!(recomputed(&mut world, &mut stage, viewer));
+
+        // Terrain changing far away and then nearby.
+        for (point, expected) in [(test_position(25, 3), false), (test_position(7, 8), true)] {
+            let mut system_state: SystemState<MapManager> = SystemState::new(&mut world);
+            system_state.get_mut(&mut world).set_terrain(point, TerrainType::Wall);
+            assert_eq!(recomputed(&mut world, &mut stage, viewer), expected);
+        }
+
+        // Moving.
+        world.get_mut::<Position>(viewer).unwrap().set_x(9);
+        assert!(recomputed(&mut world, &mut stage, viewer));
+    }
+}
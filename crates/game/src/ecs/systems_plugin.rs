@@ -10,7 +10,19 @@ impl<T: StateNext> SystemsPlugin<T> {
         // Startup
         app.add_enter_system_set(
             self.state_running,
-            ConditionSet::new().with_system(fov).into(),
+            ConditionSet::new().label("update_lights").with_system(update_lights).into(),
+        )
+        .add_enter_system_set(
+            self.state_running,
+            ConditionSet::new()
+                .label("update_viewsheds")
+                .after("update_lights")
+                .with_system(update_viewsheds)
+                .into(),
+        )
+        .add_enter_system_set(
+            self.state_running,
+            ConditionSet::new().after("update_viewsheds").with_system(fov).into(),
         );
         self
     }
@@ -41,11 +53,52 @@ impl<T: StateNext> Plugin for SystemsPlugin<T> {
         .add_system_set_to_stage(
             CoreStage::Last,
             ConditionSet::new()
+                .label("update_lights")
+                .after("cull_dead")
+                .run_in_state(self.state_running)
+                .with_system(update_lights)
+                .into(),
+        )
+        .add_system_set_to_stage(
+            CoreStage::Last,
+            // Reads the terrain changes before `update_viewsheds` takes them.
+            ConditionSet::new()
+                .label("invalidate_paths")
                 .after("cull_dead")
+                .before("update_viewsheds")
+                .run_in_state(self.state_running)
+                .with_system(invalidate_paths)
+                .into(),
+        )
+        .add_system_set_to_stage(
+            CoreStage::Last,
+            ConditionSet::new()
+                .label("update_viewsheds")
+                .after("update_lights")
+                .run_in_state(self.state_running)
+                .with_system(update_viewsheds)
+                .into(),
+        )
+        .add_system_set_to_stage(
+            CoreStage::Last,
+            ConditionSet::new()
+                .label("fov")
+                .after("update_viewsheds")
                 .run_in_state(self.state_running)
                 .with_system(fov)
                 .with_system(update_targeting)
                 .into(),
+        )
+        .add_system_set_to_stage(
+            CoreStage::Last,
+            ConditionSet::new()
+                .after("fov")
+                .run_in_state(self.state_running)
+                .with_system(remember_entities)
+                .into(),
         );
+
+        // Whichever state a world is loaded in, its ids are caught up by the end of the frame.
+        app.add_system_to_stage(CoreStage::Last, catch_up_persistent_ids);
     }
 }
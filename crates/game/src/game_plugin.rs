@@ -32,6 +32,7 @@ impl<T: StateNext> GamePlugin<T> {
             // Game Contexts
             .init_resource::<GameContext>()
             .init_resource::<AiContext>()
+            .init_resource::<PersistentIds>()
             // Turn Manager
             .init_resource::<TurnManager>();
         self
@@ -60,7 +61,8 @@ impl<T: StateNext> GamePlugin<T> {
             // UI
             .add_plugin(UiPlugin {
                 state_asset_load: self.state_asset_load,
-                state_main_menu: self.state_main_menu
+                state_main_menu: self.state_main_menu,
+                state_running: self.state_running,
             });
         self
     }
@@ -19,6 +19,9 @@ mod ai {
             pub use wander::*;
         }
         pub use actions::*;
+
+        mod update_chase_maps;
+        pub use update_chase_maps::*;
     }
     pub use systems::*;
     mod ai_plugin;
@@ -29,14 +32,24 @@ mod ecs {
     mod systems {
         mod apply_damage;
         pub use apply_damage::*;
+        mod catch_up_persistent_ids;
+        pub use catch_up_persistent_ids::*;
         mod cull_dead;
         pub use cull_dead::*;
         mod fov;
         pub use fov::*;
+        mod invalidate_paths;
+        pub use invalidate_paths::*;
         mod perform_healing;
         pub use perform_healing::*;
+        mod remember_entities;
+        pub use remember_entities::*;
+        mod update_lights;
+        pub use update_lights::*;
         mod update_targeting;
         pub use update_targeting::*;
+        mod update_viewsheds;
+        pub use update_viewsheds::*;
     }
     pub use systems::*;
 
@@ -67,6 +80,10 @@ mod player {
     mod systems {
         mod player_input;
         pub use player_input::*;
+        mod travel;
+        pub use travel::*;
+        mod update_cursor_position;
+        pub use update_cursor_position::*;
     }
     pub use systems::*;
 
@@ -91,8 +108,18 @@ mod turn {
     mod actions {
         mod attack;
         pub use attack::*;
+        mod channel;
+        pub use channel::*;
+        mod explore;
+        pub use explore::*;
         mod movement;
         pub use movement::*;
+        mod rest;
+        pub use rest::*;
+        mod search;
+        pub use search::*;
+        mod wait;
+        pub use wait::*;
     }
     pub use actions::*;
 
@@ -101,6 +128,8 @@ mod turn {
         pub use perform_action::*;
         mod perform_turns;
         pub use perform_turns::*;
+        mod suspend_unloaded_turns;
+        pub use suspend_unloaded_turns::*;
     }
     pub use systems::*;
 
@@ -110,6 +139,9 @@ mod turn {
 
 mod game_plugin;
 
+#[cfg(test)]
+mod test_world;
+
 pub mod prelude {
     mod import {
         pub use atrl_camera::prelude::*;
@@ -135,5 +167,7 @@ pub mod prelude {
     }
     pub(crate) use import::*;
 
+    #[cfg(test)]
+    pub(crate) use crate::test_world::*;
     pub use crate::{ai::*, ecs::*, events::*, game_plugin::*, player::*, spawner::*, turn::*};
 }
@@ -7,10 +7,22 @@ impl<T: StateNext> Plugin for PlayerPlugin<T> {
     fn build(&self, app: &mut App) {
         app.add_plugin(InputManagerPlugin::<PlayerAction>::default())
             .init_resource::<ActionQueue>()
+            .init_resource::<CursorPosition>()
+            .init_resource::<MessageLog>()
             .add_system_set(
                 ConditionSet::new()
+                    .label("update_cursor_position")
+                    .run_in_state(self.state_running)
+                    .with_system(update_cursor_position)
+                    .into(),
+            )
+            .add_system_set(
+                ConditionSet::new()
+                    .after("update_cursor_position")
                     .run_in_state(self.state_running)
                     .with_system(player_input)
+                    .with_system(preview_travel)
+                    .with_system(travel)
                     .with_system(draw_shape)
                     .into(),
             );
@@ -5,6 +5,9 @@ use crate::prelude::*;
 const REPEAT_DURATION: Duration = Duration::from_millis(100);
 const PRESSED_DURATION: Duration = Duration::from_millis(500);
 
+/// Turns spent searching for each press of search.
+const SEARCH_TURNS: u32 = 10;
+
 #[derive(Deref, DerefMut)]
 pub struct PlayerTimer(pub Timer);
 
@@ -30,11 +33,30 @@ pub fn player_input(
     for action_state in query.iter_mut() {
         // Actions
         if action_state.just_pressed(PlayerAction::Wait) {
-            action_queue.add_action(ActionType::Wait);
+            action_queue.clear();
+            action_queue.add_action(WaitAction);
             println!();
             info!("Player gave input: WAIT");
         }
 
+        if action_state.just_pressed(PlayerAction::Rest) {
+            action_queue.clear();
+            action_queue.add_action(RestAction::new(InterruptTriggers::ALL));
+            info!("Player gave input: REST");
+        }
+
+        if action_state.just_pressed(PlayerAction::Search) {
+            action_queue.clear();
+            action_queue.add_action(SearchAction::new(SEARCH_TURNS, InterruptTriggers::ALL));
+            info!("Player gave input: SEARCH");
+        }
+
+        if action_state.just_pressed(PlayerAction::AutoExplore) {
+            action_queue.clear();
+            action_queue.add_action(ExploreAction::new(InterruptTriggers::ALL));
+            info!("Player gave input: EXPLORE");
+        }
+
         // Movement
         for input_direction in PlayerAction::DIRECTIONS {
             if action_state.just_pressed(input_direction) ||
@@ -44,7 +66,8 @@ pub fn player_input(
             {
                 if let Some(direction) = input_direction.direction() {
                     timer.reset();
-                    action_queue.add_action(ActionType::Movement(*player_position + direction));
+                    action_queue.clear();
+                    action_queue.add_action(MoveAction::new(*player_position + direction));
 
                     println!();
                     info!("Player gave input: MOVE");
@@ -0,0 +1,136 @@
+use crate::prelude::*;
+
+/// Shows the path the player would travel along to reach the tile under the cursor.
+pub fn preview_travel(
+    tilesets: Tilesets,
+    mut commands: Commands,
+    mut map_manager: MapManager,
+    player_entity: Res<PlayerEntity>,
+    cursor_position: Res<CursorPosition>,
+    mut last_preview: Local<Option<(Position, Option<Position>)>>,
+    mut q_player: Query<(&Position, &Movement, &mut TargetVisualizer)>,
+    q_blocks_movement: Query<&BlocksMovement>,
+) {
+    let player = player_entity.current();
+    let Ok((&player_position, movement, mut target_visualizer)) = q_player.get_mut(player) else { return; };
+
+    // Only path find again when the player or the cursor moves.
+    let preview = (player_position, cursor_position.get());
+    if *last_preview == Some(preview) {
+        return;
+    }
+    *last_preview = Some(preview);
+
+    let path = travel_path(
+        &mut map_manager,
+        player_position,
+        cursor_position.get(),
+        movement.0,
+        &q_blocks_movement,
+    );
+
+    match path {
+        Some((_, path)) if !path.is_empty() => {
+            target_visualizer.update_path(
+                &mut commands,
+                &map_manager,
+                &tilesets,
+                player_position,
+                &path,
+            );
+        },
+        _ => target_visualizer.clear(&mut commands),
+    }
+}
+
+/// Starts travelling to the clicked tile, along the path `preview_travel` showed.
+pub fn travel(
+    mut map_manager: MapManager,
+    player_entity: Res<PlayerEntity>,
+    cursor_position: Res<CursorPosition>,
+    mut action_queue: ResMut<ActionQueue>,
+    q_action_state: Query<&ActionState<PlayerAction>>,
+    mut q_player: Query<(&Position, &Movement, &mut PathCache)>,
+    q_blocks_movement: Query<&BlocksMovement>,
+) {
+    if !q_action_state.iter().any(|action_state| action_state.just_pressed(PlayerAction::Travel)) {
+        return;
+    }
+
+    let player = player_entity.current();
+    let Ok((&player_position, movement, mut path_cache)) = q_player.get_mut(player) else { return; };
+
+    if let Some((destination, path)) = travel_path(
+        &mut map_manager,
+        player_position,
+        cursor_position.get(),
+        movement.0,
+        &q_blocks_movement,
+    ) {
+        // Walk the same path the preview showed.
+        path_cache.set(destination, path);
+        action_queue.clear();
+        action_queue.add_action(TravelAction::new(destination, InterruptTriggers::ALL));
+        info!("Player gave input: TRAVEL");
+    }
+}
+
+/// Path from `from` to `destination` for `preview_travel` and `travel`,
+/// as long as the destination is somewhere else the player has seen.
+fn travel_path(
+    map_manager: &mut MapManager,
+    from: Position,
+    destination: Option<Position>,
+    movement_type: u8,
+    q_blocks_movement: &Query<&BlocksMovement>,
+) -> Option<(Position, Vec<Position>)> {
+    let destination =
+        destination.filter(|&destination| destination != from && map_manager.is_explored(destination))?;
+    let path = HierarchicalPathFinder::compute(
+        from,
+        destination,
+        movement_type,
+        false,
+        map_manager,
+        q_blocks_movement,
+    )?;
+    Some((destination, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A world with an open 16x16 map where only the left half has been seen.
+    fn half_explored_world() -> World {
+        test_world_with(UVec2::new(16, 16), |map| {
+            for y in 0..16 {
+                for x in 0..8 {
+                    map.explored_tiles.insert(UVec2::new(x, y));
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn previews_the_path_to_seen_tiles() {
+        let mut world = half_explored_world();
+        let mut system_state: SystemState<(MapManager, Query<&BlocksMovement>)> =
+            SystemState::new(&mut world);
+        let (mut map_manager, q_blocks_movement) = system_state.get_mut(&mut world);
+        let walk = MovementType::Walk as u8;
+        let from = test_position(2, 2);
+
+        let cursor = Some(test_position(6, 6));
+        let (destination, path) =
+            travel_path(&mut map_manager, from, cursor, walk, &q_blocks_movement).unwrap();
+        assert_eq!(destination, test_position(6, 6));
+        assert_eq!(path.first(), Some(&destination));
+        assert_eq!(path.len(), 4);
+
+        // Unseen, under the player or off the map.
+        for cursor in [Some(test_position(12, 6)), Some(from), None] {
+            assert!(travel_path(&mut map_manager, from, cursor, walk, &q_blocks_movement).is_none());
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::prelude::*;
+
+/// Converts the mouse cursor into a `Position` on the current map through the map camera.
+pub fn update_cursor_position(
+    cameras: Cameras,
+    windows: Res<Windows>,
+    map_manager: MapManager,
+    mut cursor_position: ResMut<CursorPosition>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+) {
+    let map_size = map_manager.get_map_size();
+    let position = cameras
+        .get_camera_entity(CameraId::Map)
+        .and_then(|entity| q_camera.get(entity).ok())
+        .zip(windows.get_primary())
+        .and_then(|((camera, camera_transform), window)| {
+            let window_size = Vec2::new(window.width(), window.height());
+            let ndc_to_world = camera_transform.compute_matrix() * camera.projection_matrix().inverse();
+            let cursor = window.cursor_position()?;
+            let point = cursor_to_point(cursor, window_size, ndc_to_world, map_size)?;
+
+            Some(Position::new(
+                map_manager.get_current_world_position(),
+                LocalPosition::new(point.x, point.y, MapLayer::Terrain as u32),
+            ))
+        });
+
+    // Positions ignore their layer, which is always the same here.
+    if cursor_position.get() != position {
+        cursor_position.set(position);
+    }
+}
+
+/// The tile under `cursor`, or `None` if it's off the map.
+///
+/// Cursor is measured from the bottom left of the window.
+fn cursor_to_point(cursor: Vec2, window_size: Vec2, ndc_to_world: Mat4, map_size: UVec2) -> Option<UVec2> {
+    let ndc = (cursor / window_size) * 2.0 - Vec2::ONE;
+    let world = ndc_to_world.project_point3(ndc.extend(-1.0)).truncate().floor();
+    if world.cmplt(Vec2::ZERO).any() || world.cmpge(map_size.as_vec2()).any() {
+        return None;
+    }
+
+    Some(world.as_uvec2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 10 pixels per tile.
+    const WINDOW_SIZE: Vec2 = Vec2::new(800.0, 450.0);
+
+    /// `ndc_to_world` for a camera looking down on `center`, showing 80x45 tiles.
+    fn camera_at(center: Vec2) -> Mat4 {
+        let projection = Mat4::orthographic_rh(-40.0, 40.0, -22.5, 22.5, 0.0, 1000.0);
+        Mat4::from_translation(center.extend(999.0)) * projection.inverse()
+    }
+
+    #[test]
+    fn converts_the_cursor_to_tiles() {
+        let ndc_to_world = camera_at(Vec2::new(40.0, 22.5));
+        let map_size = UVec2::new(80, 45);
+        for (cursor, point) in [
+            (Vec2::new(5.0, 5.0), UVec2::new(0, 0)),
+            (Vec2::new(405.0, 225.0), UVec2::new(40, 22)),
+            (Vec2::new(795.0, 445.0), UVec2::new(79, 44)),
+        ] {
+            assert_eq!(
+                cursor_to_point(cursor, WINDOW_SIZE, ndc_to_world, map_size),
+                Some(point)
+            );
+        }
+    }
+
+    #[test]
+    fn stays_on_the_current_map() {
+        // A smaller map than the screen.
+        let ndc_to_world = camera_at(Vec2::new(40.0, 22.5));
+        let map_size = UVec2::new(40, 30);
+        assert_eq!(
+            cursor_to_point(Vec2::new(395.0, 225.0), WINDOW_SIZE, ndc_to_world, map_size),
+            Some(UVec2::new(39, 22))
+        );
+        assert_eq!(
+            cursor_to_point(Vec2::new(405.0, 225.0), WINDOW_SIZE, ndc_to_world, map_size),
+            None
+        );
+        assert_eq!(
+            cursor_to_point(Vec2::new(395.0, 305.0), WINDOW_SIZE, ndc_to_world, map_size),
+            None
+        );
+
+        // Panned past the bottom left corner.
+        let ndc_to_world = camera_at(Vec2::new(20.0, 15.0));
+        assert_eq!(
+            cursor_to_point(Vec2::new(5.0, 5.0), WINDOW_SIZE, ndc_to_world, map_size),
+            None
+        );
+    }
+}
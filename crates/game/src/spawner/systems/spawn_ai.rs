@@ -8,6 +8,7 @@ pub fn spawn_ai(
     state: Res<CurrentGameState>,
     mut map_manager: MapManager,
     mut turn_manager: ResMut<TurnManager>,
+    mut persistent_ids: ResMut<PersistentIds>,
     q_blocks_movement: Query<&BlocksMovement>,
 ) {
     let Some(tileset) = tilesets.get_by_id(&TILESET_ACTORS_ID) else {
@@ -38,6 +39,7 @@ pub fn spawn_ai(
                 let ai_entity = spawn_ai_at(
                     &mut commands,
                     tileset.atlas(),
+                    persistent_ids.next_id(),
                     format!("Gary ({})", actor_count).as_str(),
                     position,
                     vision_type,
@@ -61,6 +63,7 @@ pub fn spawn_ai(
 fn spawn_ai_at(
     commands: &mut Commands,
     texture_atlas: &Handle<TextureAtlas>,
+    persistent_id: PersistentId,
     name: &str,
     position: Position,
     vision_type: VisionType,
@@ -82,6 +85,7 @@ fn spawn_ai_at(
         .spawn((
             ActorBundle {
                 mob: Mob,
+                persistent_id,
                 position,
                 ai: AIComponent::aggressive(),
                 name: Name::new(name.to_string()),
@@ -99,8 +103,13 @@ fn spawn_ai_at(
                 },
 
                 fov: FieldOfView(8),
+                fov_algorithm: Fov::Shadowcast,
+                facing: Facing::from_direction(GridDirection::South, 60.0),
+                viewshed: Viewshed::new(),
                 vision_component: Vision(vision_type.as_u8()),
                 movement_component: Movement(movement_type.as_u8()),
+                path_cache: PathCache::new(),
+                speed: Speed::default(),
                 target_visualizer: TargetVisualizer::default(),
             },
             thinker,
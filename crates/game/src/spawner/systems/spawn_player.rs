@@ -5,6 +5,7 @@ pub fn spawn_player(
     mut commands: Commands,
     mut map_manager: MapManager,
     mut turn_manager: ResMut<TurnManager>,
+    mut persistent_ids: ResMut<PersistentIds>,
     q_blocks_movement: Query<&BlocksMovement>,
 ) {
     let Some(tileset) = tilesets.get_by_id(&TILESET_ACTORS_ID) else {
@@ -35,6 +36,7 @@ pub fn spawn_player(
             actor: ActorBundle {
                 position,
                 mob: Mob,
+                persistent_id: persistent_ids.next_id(),
                 name: Name::new("Bob the Builder"),
                 health: Health::full(10),
                 ai: AIComponent::human(),
@@ -51,10 +53,16 @@ pub fn spawn_player(
                 },
 
                 fov: FieldOfView(16),
+                fov_algorithm: Fov::Shadowcast,
+                facing: Facing::default(),
+                viewshed: Viewshed::new(),
                 vision_component: Vision(VisionType::Normal.as_u8()),
                 movement_component: Movement(movement_type),
+                path_cache: PathCache::new(),
+                speed: Speed::default(),
                 target_visualizer: TargetVisualizer::default(),
             },
+            light: LightSource::default(),
             input_manager: InputManagerBundle {
                 input_map: PlayerBundle::default_input_map(),
                 ..default()
@@ -0,0 +1,43 @@
+use crate::prelude::*;
+
+/// Position on the actor layer of the test map.
+pub fn test_position(x: u32, y: u32) -> Position {
+    Position::new(
+        WorldPosition::ZERO,
+        LocalPosition::new(x, y, MapLayer::Actors as u32),
+    )
+}
+
+/// A world with an open map of floor, `size` tiles big, loaded at `WorldPosition::ZERO`.
+pub fn test_world(size: UVec2) -> World { test_world_with(size, |_| {}) }
+
+/// Same as `test_world`, with `setup` run on the map before it's loaded.
+pub fn test_world_with(size: UVec2, setup: impl FnOnce(&mut Map)) -> World {
+    let mut world = World::new();
+    world.insert_resource(GameContext {
+        random: Random::new(0),
+    });
+
+    let mut map = test_map(size, WorldPosition::ZERO);
+    setup(&mut map);
+
+    world.insert_resource(MapManagerResource::new(
+        WorldPosition::ZERO,
+        map,
+        Entity::from_raw(1),
+        Entity::from_raw(2),
+        Vec::new(),
+    ));
+    world
+}
+
+/// An open map of floor, `size` tiles big, at `world_position`.
+pub fn test_map(size: UVec2, world_position: WorldPosition) -> Map {
+    let user_data = MapPassThroughData {
+        map_entity: Entity::from_raw(0),
+        world_position,
+        ambient_light: DEFAULT_AMBIENT_LIGHT,
+    };
+    let floor = SetBuilder::new().set_value(TerrainType::Floor as u32);
+    Map::from(MapGenerator::new(size, Random::new(0), floor, user_data).generate())
+}
@@ -1,9 +1,33 @@
 use crate::prelude::*;
 
-pub fn try_attack(entity: Entity, position: Position, world: &mut World) -> Result<(), ActionType> {
-    let mut system_state: SystemState<(MapManager, Query<(&mut Health, &Name)>)> = SystemState::new(world);
+/// Hit whatever has health at `target`.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy)]
+pub struct AttackAction {
+    pub target: Position,
+}
+
+impl AttackAction {
+    pub const fn new(target: Position) -> Self { Self { target } }
+}
+
+impl Action for AttackAction {
+    fn kind(&self) -> ActionKind { ActionKind::Attack }
 
-    let (mut map_manager, mut health_q) = system_state.get_mut(world);
+    fn target(&self) -> Option<Position> { Some(self.target) }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { ATTACK_TIME }
+
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        try_attack(entity, self.target, world).map(|_| ActionOutcome::Performed)
+    }
+}
+
+pub fn try_attack(entity: Entity, position: Position, world: &mut World) -> Result<(), ActionFailure> {
+    let player_entity = world.get_resource::<PlayerEntity>().map(|player_entity| player_entity.current());
+    let mut system_state: SystemState<(MapManager, Query<(&mut Health, &Name)>, Query<&Name>)> =
+        SystemState::new(world);
+
+    let (mut map_manager, mut health_q, name_q) = system_state.get_mut(world);
 
     let mut actors = Vec::new();
     let mut features = Vec::new();
@@ -16,25 +40,33 @@ pub fn try_attack(entity: Entity, position: Position, world: &mut World) -> Resu
         features = victims.clone();
     }
 
+    let attacker = name_q.get(entity).map_or_else(|_| format!("{:?}", entity), |name| name.to_string());
     let mut has_attacked = false;
+    let mut messages = Vec::new();
     for victim in actors.iter().chain(features.iter()) {
         if let Ok((mut health, name)) = health_q.get_mut(*victim) {
             has_attacked = true;
-            let before = format!("{}/{}", health.current_hp, health.max_hp);
             health.current_hp -= 1;
-            let after = format!("{}/{}", health.current_hp, health.max_hp);
 
-            println!(
-                "{} is attacking {:?} before: ({}) after: ({})",
-                name, entity, before, after
-            );
+            // Only fights the player is part of are worth telling them about.
+            if player_entity.map_or(false, |player| player == entity || player == *victim) {
+                messages.push(format!(
+                    "{} attacks {} ({}/{}).",
+                    attacker, name, health.current_hp, health.max_hp
+                ));
+            }
+        }
+    }
+
+    if let Some(mut message_log) = world.get_resource_mut::<MessageLog>() {
+        for message in messages {
+            message_log.add(message);
         }
     }
 
     if has_attacked {
         Ok(())
     } else {
-        info!("Couldn't find entities with health components.");
-        Err(ActionType::Wait)
+        Err(ActionFailure::NoTarget(position))
     }
 }
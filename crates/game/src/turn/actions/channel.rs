@@ -0,0 +1,44 @@
+use crate::prelude::*;
+
+/// Channel for a number of turns, then perform `action`.
+/// Interrupting the channel loses the action.
+#[derive(Reflect, FromReflect, Debug, Default)]
+pub struct ChannelAction {
+    pub turns_left: u32,
+    pub interrupts: Interrupts,
+    #[reflect(ignore)]
+    pub action: Option<BoxedAction>,
+}
+
+impl ChannelAction {
+    pub fn new(turns: u32, action: impl Action, triggers: InterruptTriggers) -> Self {
+        Self {
+            turns_left: turns,
+            interrupts: Interrupts::new(triggers),
+            action: Some(Box::new(action)),
+        }
+    }
+}
+
+impl Action for ChannelAction {
+    fn target(&self) -> Option<Position> { self.action.as_ref().and_then(|action| action.target()) }
+
+    fn validate(&self, entity: Entity, world: &mut World) -> Result<(), ActionFailure> {
+        self.action.as_ref().map_or(Err(ActionFailure::NothingToChannel), |action| {
+            action.validate(entity, world)
+        })
+    }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { TURN_TIME }
+
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        self.interrupts.check(entity, world)?;
+
+        if self.turns_left > 0 {
+            self.turns_left -= 1;
+            return Ok(ActionOutcome::Continue);
+        }
+
+        self.action.take().map(ActionOutcome::Alternate).ok_or(ActionFailure::NothingToChannel)
+    }
+}
@@ -0,0 +1,152 @@
+use crate::prelude::*;
+
+/// Walk towards the nearest reachable tile bordering unexplored space, a step each turn,
+/// until there's nothing left to explore.
+#[derive(Reflect, FromReflect, Debug, Default, Clone)]
+pub struct ExploreAction {
+    pub interrupts: Interrupts,
+    /// The tile currently being explored towards.
+    #[reflect(ignore)]
+    target: Option<Position>,
+    /// Tiles which were reached while their neighbours stayed unexplored,
+    /// such as when they are too dark to see.
+    #[reflect(ignore)]
+    given_up: HashSet<Position>,
+}
+
+impl ExploreAction {
+    pub fn new(triggers: InterruptTriggers) -> Self {
+        Self {
+            interrupts: Interrupts::new(triggers),
+            ..Default::default()
+        }
+    }
+}
+
+impl Action for ExploreAction {
+    fn kind(&self) -> ActionKind { ActionKind::Movement }
+
+    fn target(&self) -> Option<Position> { self.target }
+
+    fn validate(&self, entity: Entity, world: &mut World) -> Result<(), ActionFailure> {
+        if world.get::<Position>(entity).is_none() || world.get::<Movement>(entity).is_none() {
+            return Err(ActionFailure::MissingComponents);
+        }
+        Ok(())
+    }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { TURN_TIME }
+
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        self.interrupts.check(entity, world)?;
+
+        let mut system_state: SystemState<(
+            MapManager,
+            Query<(&Position, &Movement)>,
+            Query<&BlocksMovement>,
+        )> = SystemState::new(world);
+        let (mut map_manager, q_explorer, q_blocks_movement) = system_state.get_mut(world);
+        let (&position, movement) = q_explorer.get(entity).map_err(|_| ActionFailure::MissingComponents)?;
+
+        // Reaching the target without seeing what's next to it means it never will be.
+        if self.target == Some(position) {
+            self.given_up.insert(position);
+        }
+
+        let target = map_manager
+            .find_unexplored(position, movement.0, &q_blocks_movement, &self.given_up)
+            .ok_or(ActionFailure::NothingToExplore)?;
+        self.target = Some(target);
+
+        try_move(entity, target, world)?;
+        Ok(ActionOutcome::Continue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A world with an open 16x16 map where the columns left of `explored_width`
+    /// have been seen, and `walls` put up.
+    fn explored_world(explored_width: u32, walls: &[Position]) -> World {
+        test_world_with(UVec2::new(16, 16), |map| {
+            for y in 0..16 {
+                for x in 0..explored_width {
+                    map.explored_tiles.insert(UVec2::new(x, y));
+                }
+            }
+            for wall in walls {
+                map.set_terrain(wall.get_local_position(), TerrainType::Wall);
+            }
+        })
+    }
+
+    fn find_unexplored(world: &mut World, origin: Position, skip: &HashSet<Position>) -> Option<Position> {
+        let mut system_state: SystemState<(MapManager, Query<&BlocksMovement>)> = SystemState::new(world);
+        let (mut map_manager, q_blocks_movement) = system_state.get_mut(world);
+        map_manager.find_unexplored(origin, MovementType::Walk as u8, &q_blocks_movement, skip)
+    }
+
+    fn spawn_explorer(world: &mut World, position: Position) -> Entity {
+        let movement_type = MovementType::Walk as u8;
+        let explorer = world.spawn((position, Movement(movement_type))).id();
+        let mut system_state: SystemState<(MapManager, Query<&BlocksMovement>)> = SystemState::new(world);
+        let (mut map_manager, q_blocks_movement) = system_state.get_mut(world);
+        assert!(map_manager.add_actor(explorer, position, movement_type, &q_blocks_movement));
+        explorer
+    }
+
+    #[test]
+    fn finds_the_nearest_frontier() {
+        let mut world = explored_world(6, &[]);
+        let origin = test_position(1, 8);
+
+        let frontier = find_unexplored(&mut world, origin, &HashSet::new()).unwrap();
+        assert_eq!(frontier.x(), 5);
+        assert_eq!(origin.distance(frontier), 4);
+
+        // Given up on, so the next nearest is picked instead.
+        let mut skip = HashSet::new();
+        skip.insert(frontier);
+        let next = find_unexplored(&mut world, origin, &skip).unwrap();
+        assert_ne!(next, frontier);
+        assert_eq!(next.x(), 5);
+    }
+
+    #[test]
+    fn stops_when_nothing_is_left() {
+        let (origin, skip) = (test_position(1, 8), HashSet::new());
+
+        // Everything seen.
+        let mut world = explored_world(16, &[]);
+        assert_eq!(find_unexplored(&mut world, origin, &skip), None);
+
+        // The unexplored side is walled off.
+        let walls: Vec<Position> = (0..16).map(|y| test_position(3, y)).collect();
+        let mut world = explored_world(6, &walls);
+        assert_eq!(find_unexplored(&mut world, origin, &skip), None);
+    }
+
+    #[test]
+    fn explores_until_nothing_is_left() {
+        let mut world = explored_world(6, &[]);
+        let explorer = spawn_explorer(&mut world, test_position(1, 8));
+        let mut explore = ExploreAction::new(InterruptTriggers::NONE);
+        for x in 2..=5 {
+            assert!(matches!(
+                explore.perform(explorer, &mut world),
+                Ok(ActionOutcome::Continue)
+            ));
+            assert_eq!(world.get::<Position>(explorer).unwrap().x(), x);
+        }
+
+        let mut world = explored_world(16, &[]);
+        let explorer = spawn_explorer(&mut world, test_position(1, 8));
+        let mut explore = ExploreAction::new(InterruptTriggers::NONE);
+        assert!(matches!(
+            explore.perform(explorer, &mut world),
+            Err(ActionFailure::NothingToExplore)
+        ));
+    }
+}
@@ -1,5 +1,96 @@
 use crate::prelude::*;
 
+/// Take a step towards `destination`, which can be any distance away.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy)]
+pub struct MoveAction {
+    pub destination: Position,
+}
+
+impl MoveAction {
+    pub const fn new(destination: Position) -> Self { Self { destination } }
+}
+
+impl Action for MoveAction {
+    fn kind(&self) -> ActionKind { ActionKind::Movement }
+
+    fn target(&self) -> Option<Position> { Some(self.destination) }
+
+    fn validate(&self, entity: Entity, world: &mut World) -> Result<(), ActionFailure> {
+        if world.get::<Position>(entity).is_none() || world.get::<Movement>(entity).is_none() {
+            return Err(ActionFailure::MissingComponents);
+        }
+        Ok(())
+    }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { TURN_TIME }
+
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        try_move(entity, self.destination, world).map(|_| ActionOutcome::Performed)
+    }
+}
+
+/// Keep moving towards `destination`, a step each turn until getting there.
+#[derive(Reflect, FromReflect, Debug, Default, Clone)]
+pub struct TravelAction {
+    pub destination: Position,
+    pub interrupts: Interrupts,
+}
+
+impl TravelAction {
+    pub fn new(destination: Position, triggers: InterruptTriggers) -> Self {
+        Self {
+            destination,
+            interrupts: Interrupts::new(triggers),
+        }
+    }
+}
+
+impl Action for TravelAction {
+    fn kind(&self) -> ActionKind { ActionKind::Movement }
+
+    fn target(&self) -> Option<Position> { Some(self.destination) }
+
+    fn validate(&self, entity: Entity, world: &mut World) -> Result<(), ActionFailure> {
+        MoveAction::new(self.destination).validate(entity, world)
+    }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { TURN_TIME }
+
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        self.interrupts.check(entity, world)?;
+
+        try_move(entity, self.destination, world)?;
+        if world.get::<Position>(entity) == Some(&self.destination) {
+            Ok(ActionOutcome::Performed)
+        } else {
+            Ok(ActionOutcome::Continue)
+        }
+    }
+}
+
+/// Step by `delta` from wherever the actor is when its turn comes up.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy)]
+pub struct StepAction {
+    pub delta: IVec2,
+}
+
+impl StepAction {
+    pub const fn new(delta: IVec2) -> Self { Self { delta } }
+}
+
+impl Action for StepAction {
+    fn kind(&self) -> ActionKind { ActionKind::Movement }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { TURN_TIME }
+
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        let position = world.get::<Position>(entity).ok_or(ActionFailure::MissingComponents)?;
+        Ok(ActionOutcome::Alternate(Box::new(MoveAction::new(
+            *position + self.delta,
+        ))))
+    }
+}
+
 pub fn try_move(
     entity: Entity,
     destination: Position,
@@ -7,10 +98,15 @@ pub fn try_move(
     // map_manager: &mut ResMut<MapManager>,
     // q_position: &mut Query<&mut Position>,
     // q_movement: &Query<&Movement>,
-) -> Result<(), ActionType> {
+) -> Result<(), ActionFailure> {
     let mut system_state: SystemState<(
         MapManager,
-        Query<(&mut Position, &Movement)>,
+        Query<(
+            &mut Position,
+            &Movement,
+            Option<&mut Facing>,
+            Option<&mut PathCache>,
+        )>,
         Query<&BlocksMovement>,
     )> = SystemState::new(world);
     let (mut map_manager, mut spatial_q, q_blocks_movement) = system_state.get_mut(world);
@@ -18,47 +114,62 @@ pub fn try_move(
     spatial_q.get_mut(entity).map_or_else(
         |err| {
             info!("Couldn't find entities position components: {}", err);
-            Err(ActionType::Wait)
+            Err(ActionFailure::MissingComponents)
         },
-        |(mut from_position, movement_component)| {
-            PathFinder::Astar
-                .compute(
-                    *from_position,
-                    destination,
-                    movement_component.0,
-                    true,
-                    &mut map_manager,
-                    &q_blocks_movement,
-                )
-                .map_or_else(
-                    || {
-                        info!("Couldn't find a path to {:?}", destination);
-                        Err(ActionType::Wait)
-                    },
-                    |mut path| {
-                        path.pop().map_or_else(
-                            || {
-                                info!("Couldn't find a long enough path to {:?}", destination);
-                                Err(ActionType::Wait)
-                            },
-                            |destination| {
-                                if map_manager.move_actor(
-                                    entity,
-                                    *from_position,
-                                    destination,
-                                    movement_component.0,
-                                    &q_blocks_movement,
-                                ) {
-                                    from_position.set_xy(destination.gridpoint());
-                                    Ok(())
-                                } else {
-                                    info!("{:?} is blocked!", destination);
-                                    Err(ActionType::Wait)
-                                }
-                            },
-                        )
-                    },
-                )
+        |(mut from_position, movement_component, facing, mut path_cache)| {
+            // Keep following the cached route while it still leads to the destination.
+            let cached_step =
+                path_cache.as_mut().and_then(|path_cache| path_cache.next_step(*from_position, destination));
+
+            cached_step
+                .map(Ok)
+                .unwrap_or_else(|| {
+                    // Destinations on other maps route through their exits.
+                    HierarchicalPathFinder::compute(
+                        *from_position,
+                        destination,
+                        movement_component.0,
+                        true,
+                        &mut map_manager,
+                        &q_blocks_movement,
+                    )
+                    .map_or_else(
+                        || Err(ActionFailure::NoPath(destination)),
+                        |mut path| {
+                            path.pop().map_or_else(
+                                || Err(ActionFailure::NoPath(destination)),
+                                |next_step| {
+                                    if let Some(path_cache) = path_cache.as_mut() {
+                                        path_cache.set(destination, path);
+                                    }
+                                    Ok(next_step)
+                                },
+                            )
+                        },
+                    )
+                })
+                .and_then(|next_step| {
+                    if map_manager.move_actor(
+                        entity,
+                        *from_position,
+                        next_step,
+                        movement_component.0,
+                        &q_blocks_movement,
+                    ) {
+                        if let Some(mut facing) = facing {
+                            facing.look_at(*from_position, next_step);
+                        }
+                        from_position.set_world_xyz(next_step.get_world_position().xyz());
+                        from_position.set_xy(next_step.gridpoint());
+                        Ok(())
+                    } else {
+                        // Something stepped onto the route, find a way around it next time.
+                        if let Some(mut path_cache) = path_cache {
+                            path_cache.invalidate();
+                        }
+                        Err(ActionFailure::Blocked(next_step))
+                    }
+                })
         },
     )
 }
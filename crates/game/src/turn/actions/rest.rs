@@ -0,0 +1,44 @@
+use crate::prelude::*;
+
+/// Health regained for each turn spent resting.
+const REST_HEALING: i32 = 1;
+
+/// Rest until fully healed, a turn at a time.
+#[derive(Reflect, FromReflect, Debug, Default, Clone)]
+pub struct RestAction {
+    pub interrupts: Interrupts,
+}
+
+impl RestAction {
+    pub fn new(triggers: InterruptTriggers) -> Self {
+        Self {
+            interrupts: Interrupts::new(triggers),
+        }
+    }
+}
+
+impl Action for RestAction {
+    fn kind(&self) -> ActionKind { ActionKind::Wait }
+
+    fn validate(&self, entity: Entity, world: &mut World) -> Result<(), ActionFailure> {
+        let health = world.get::<Health>(entity).ok_or(ActionFailure::MissingComponents)?;
+        if health.current_hp >= health.max_hp {
+            return Err(ActionFailure::AtFullHealth);
+        }
+        Ok(())
+    }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { WAIT_TIME }
+
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        self.interrupts.check(entity, world)?;
+
+        let mut health = world.get_mut::<Health>(entity).ok_or(ActionFailure::MissingComponents)?;
+        health.current_hp = (health.current_hp + REST_HEALING).min(health.max_hp);
+        if health.current_hp < health.max_hp {
+            Ok(ActionOutcome::Continue)
+        } else {
+            Ok(ActionOutcome::Performed)
+        }
+    }
+}
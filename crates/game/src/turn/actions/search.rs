@@ -0,0 +1,37 @@
+use crate::prelude::*;
+
+/// Search the surroundings for a number of turns.
+///
+/// There's nothing hidden to find yet, so for now this passes the time like waiting.
+#[derive(Reflect, FromReflect, Debug, Default, Clone)]
+pub struct SearchAction {
+    pub turns_left: u32,
+    pub interrupts: Interrupts,
+}
+
+impl SearchAction {
+    pub fn new(turns: u32, triggers: InterruptTriggers) -> Self {
+        Self {
+            turns_left: turns,
+            interrupts: Interrupts::new(triggers),
+        }
+    }
+}
+
+impl Action for SearchAction {
+    fn kind(&self) -> ActionKind { ActionKind::Wait }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { WAIT_TIME }
+
+    fn perform(&mut self, entity: Entity, world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        self.interrupts.check(entity, world)?;
+
+        info!("Searching");
+        self.turns_left = self.turns_left.saturating_sub(1);
+        if self.turns_left > 0 {
+            Ok(ActionOutcome::Continue)
+        } else {
+            Ok(ActionOutcome::Performed)
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use crate::prelude::*;
+
+/// Do nothing for a turn.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy)]
+pub struct WaitAction;
+
+impl Action for WaitAction {
+    fn kind(&self) -> ActionKind { ActionKind::Wait }
+
+    fn cost(&self, _entity: Entity, _world: &World) -> u32 { WAIT_TIME }
+
+    fn perform(&mut self, _entity: Entity, _world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+        info!("Waiting");
+        Ok(ActionOutcome::Performed)
+    }
+}
@@ -1,24 +1,153 @@
 use crate::prelude::*;
 
-pub fn perform_action(entity: Entity, action: ActionType, world: &mut World) -> Result<u32, ActionType> {
-    match action {
-        ActionType::Wait => {
-            info!("Waiting");
-            Ok(ActionType::Wait.get_base_time_to_perform())
-        },
-        ActionType::MovementDelta(delta) => {
-            let mut position_q = world.query::<&mut Position>();
-            position_q.get(world, entity).map_or(Err(ActionType::Wait), |entity_position| {
-                Err(ActionType::Movement(*entity_position + delta))
-            })
-        },
-        ActionType::Movement(destination) => match try_move(entity, destination, world) {
-            Ok(_) => Ok(action.get_base_time_to_perform()),
-            Err(a) => Err(a),
-        },
-        ActionType::Attack(position) => match try_attack(entity, position, world) {
-            Ok(_) => Ok(action.get_base_time_to_perform()),
-            Err(a) => Err(a),
-        },
+/// Give up on an action after it has handed over to this many alternates,
+/// in case a couple of actions keep handing over to each other.
+const MAX_ALTERNATES: usize = 8;
+
+/// Validate and perform `action`, following any alternate it hands over to.
+///
+/// Returns the time spent, along with the action to carry on with on the entity's next turn
+/// if it takes several turns. Otherwise returns why the action couldn't be performed.
+pub fn perform_action(
+    entity: Entity,
+    action: BoxedAction,
+    world: &mut World,
+) -> Result<(u32, Option<BoxedAction>), ActionFailure> {
+    let mut action = action;
+    for _ in 0..=MAX_ALTERNATES {
+        action.validate(entity, world)?;
+        let is_ongoing = match action.perform(entity, world)? {
+            ActionOutcome::Performed => false,
+            ActionOutcome::Continue => true,
+            ActionOutcome::Alternate(alternate) => {
+                action = alternate;
+                continue;
+            },
+        };
+
+        let time_spent = time_to_perform(entity, action.as_ref(), world);
+        return Ok((time_spent, is_ongoing.then_some(action)));
+    }
+
+    Err(ActionFailure::Other(format!(
+        "{:?} kept handing over to other actions.",
+        action
+    )))
+}
+
+/// Time `action` took `entity`, sped up or slowed down by its `Speed`.
+fn time_to_perform(entity: Entity, action: &dyn Action, world: &mut World) -> u32 {
+    let base_time = action.cost(entity, world);
+    let mut speed_q = world.query::<(&Speed, Option<&Movement>)>();
+    speed_q.get(world, entity).map_or(base_time, |(speed, movement)| {
+        speed.time_to_perform(
+            base_time,
+            action.kind(),
+            movement.map_or(0, |movement| movement.0),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands over to another `Relay` until `hops_left` runs out.
+    #[derive(Debug)]
+    struct Relay {
+        hops_left: usize,
+    }
+
+    impl Action for Relay {
+        fn cost(&self, _entity: Entity, _world: &World) -> u32 { TURN_TIME }
+
+        fn perform(&mut self, _entity: Entity, _world: &mut World) -> Result<ActionOutcome, ActionFailure> {
+            match self.hops_left {
+                0 => Ok(ActionOutcome::Performed),
+                hops_left => Ok(ActionOutcome::Alternate(Box::new(Self {
+                    hops_left: hops_left - 1,
+                }))),
+            }
+        }
+    }
+
+    #[test]
+    fn validation_failures_are_returned() {
+        let mut world = World::new();
+        // Nothing can move without `Movement`.
+        let entity = world.spawn(test_position(2, 2)).id();
+        let action = Box::new(MoveAction::new(test_position(3, 2)));
+        assert_eq!(
+            perform_action(entity, action, &mut world).err(),
+            Some(ActionFailure::MissingComponents)
+        );
+    }
+
+    #[test]
+    fn steps_hand_over_to_moves() {
+        let mut world = test_world(UVec2::new(16, 16));
+        let (position, movement_type) = (test_position(2, 2), MovementType::Walk as u8);
+        let walker = world.spawn((position, Movement(movement_type))).id();
+        let mut system_state: SystemState<(MapManager, Query<&BlocksMovement>)> =
+            SystemState::new(&mut world);
+        let (mut map_manager, q_blocks_movement) = system_state.get_mut(&mut world);
+        assert!(map_manager.add_actor(walker, position, movement_type, &q_blocks_movement));
+
+        let action = Box::new(StepAction::new(IVec2::X));
+        let (time_spent, ongoing_action) = perform_action(walker, action, &mut world).unwrap();
+        assert_eq!(time_spent, TURN_TIME);
+        assert!(ongoing_action.is_none());
+        assert_eq!(world.get::<Position>(walker), Some(&test_position(3, 2)));
+    }
+
+    #[test]
+    fn gives_up_after_max_alternates() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+
+        let relay = |hops_left| Box::new(Relay { hops_left });
+        assert!(perform_action(entity, relay(MAX_ALTERNATES), &mut world).is_ok());
+        assert!(matches!(
+            perform_action(entity, relay(MAX_ALTERNATES + 1), &mut world),
+            Err(ActionFailure::Other(_))
+        ));
+    }
+
+    #[test]
+    fn only_the_ai_loses_time_to_failures() {
+        let mut world = World::new();
+        world.init_resource::<TurnManager>();
+        world.init_resource::<ActionQueue>();
+        world.init_resource::<MessageLog>();
+
+        // Neither of them can move without `Movement`.
+        let destination = test_position(3, 3);
+        let mut ai_component = AIComponent::aggressive();
+        ai_component.preferred_action = Some(Box::new(MoveAction::new(destination)));
+        let ai = world.spawn((Name::new("Monster"), test_position(4, 4), ai_component)).id();
+        let player = world.spawn((Name::new("Player"), test_position(2, 2))).id();
+        world.insert_resource(PlayerEntity::new(player));
+        world.resource_mut::<ActionQueue>().add_action(MoveAction::new(destination));
+        world.resource_mut::<TurnManager>().add_entity(ai);
+        world.resource_mut::<TurnManager>().add_entity(player);
+
+        perform_turns(&mut world);
+        assert_eq!(
+            world.resource::<MessageLog>().last(),
+            Some(&ActionFailure::MissingComponents.to_string())
+        );
+
+        // The player chooses again straight away, while the AI waits.
+        let mut turn_manager = world.resource_mut::<TurnManager>();
+        assert_eq!(turn_manager.start_entity_turn(), Some(player));
+        assert_eq!(
+            (turn_manager.turn_number(), turn_manager.current_time()),
+            (0, 0)
+        );
+        assert_eq!(turn_manager.start_entity_turn(), Some(ai));
+        assert_eq!(
+            (turn_manager.turn_number(), turn_manager.current_time()),
+            (WAIT_TIME / TURN_TIME, WAIT_TIME % TURN_TIME)
+        );
     }
 }
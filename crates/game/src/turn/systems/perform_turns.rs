@@ -9,17 +9,19 @@ pub fn perform_turns(world: &mut World) {
                 let mut ai_q = world.query::<(&mut AIComponent, &Name)>();
                 let mut action_queue = world.resource_mut::<ActionQueue>();
 
-                let mut action = if is_player {
+                let action = if is_player {
                     if let Some(a) = action_queue.get_action() {
                         a
                     } else {
                         turn_manager.end_entity_turn(entity, 0);
                         return;
                     }
-                } else if let Ok((ai_component, name)) = ai_q.get_mut(world, entity) {
+                } else if let Ok((mut ai_component, name)) = ai_q.get_mut(world, entity) {
                     info!("Starting turn for {}", name);
 
-                    if let Some(a) = ai_component.preferred_action {
+                    let action =
+                        ai_component.ongoing_action.take().or_else(|| ai_component.preferred_action.take());
+                    if let Some(a) = action {
                         info!("{} is performing {:?}", name, a);
                         a
                     } else {
@@ -34,14 +36,33 @@ pub fn perform_turns(world: &mut World) {
                     return;
                 };
 
-                loop {
-                    match perform_action(entity, action, world) {
-                        Ok(time_spent) => {
-                            turn_manager.end_entity_turn(entity, time_spent);
-                            break;
-                        },
-                        Err(a) => action = a,
-                    }
+                match perform_action(entity, action, world) {
+                    Ok((time_spent, ongoing_action)) => {
+                        if let Some(mut speed) = world.get_mut::<Speed>(entity) {
+                            speed.tick(time_spent);
+                        }
+
+                        // Multi-turn actions carry on at the entity's next turn.
+                        if let Some(action) = ongoing_action {
+                            if is_player {
+                                world.resource_mut::<ActionQueue>().add_next(action);
+                            } else if let Some(mut ai_component) = world.get_mut::<AIComponent>(entity) {
+                                ai_component.ongoing_action = Some(action);
+                            }
+                        }
+                        turn_manager.end_entity_turn(entity, time_spent);
+                    },
+                    Err(failure) if is_player => {
+                        // Nothing happened, so stop and let the player choose again.
+                        world.resource_mut::<ActionQueue>().clear();
+                        world.resource_mut::<MessageLog>().add(failure.to_string());
+                        turn_manager.end_entity_turn(entity, 0);
+                        return;
+                    },
+                    Err(failure) => {
+                        info!("{:?} couldn't act: {}", entity, failure);
+                        turn_manager.end_entity_turn(entity, WAIT_TIME);
+                    },
                 }
 
                 if is_player {
@@ -57,3 +78,104 @@ pub fn perform_turns(world: &mut World) {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A world with an empty map and the player at (2, 2), the only one taking turns.
+    fn player_world() -> (World, Entity) {
+        let mut world = test_world(UVec2::new(16, 16));
+        world.init_resource::<TurnManager>();
+        world.init_resource::<ActionQueue>();
+        world.init_resource::<MessageLog>();
+
+        let (position, movement_type) = (test_position(2, 2), MovementType::Walk as u8);
+        let player = world
+            .spawn((
+                Name::new("Player"),
+                position,
+                Movement(movement_type),
+                Health::new(7, 10),
+            ))
+            .id();
+        let mut system_state: SystemState<(MapManager, Query<&BlocksMovement>)> =
+            SystemState::new(&mut world);
+        let (mut map_manager, q_blocks_movement) = system_state.get_mut(&mut world);
+        assert!(map_manager.add_actor(player, position, movement_type, &q_blocks_movement));
+
+        world.insert_resource(PlayerEntity::new(player));
+        world.resource_mut::<TurnManager>().add_entity(player);
+        (world, player)
+    }
+
+    /// Runs the player's next turn, checking it was the `turn_number`th turn.
+    fn player_turn(world: &mut World, turn_number: u32) {
+        perform_turns(world);
+        assert_eq!(world.resource::<TurnManager>().turn_number(), turn_number);
+    }
+
+    #[test]
+    fn rest_continues_until_healed() {
+        let (mut world, player) = player_world();
+        world.resource_mut::<ActionQueue>().add_action(RestAction::new(InterruptTriggers::ALL));
+
+        for (turn_number, hp) in (8..=10).enumerate() {
+            player_turn(&mut world, turn_number as u32);
+            assert_eq!(world.get::<Health>(player).unwrap().current_hp, hp);
+            assert_eq!(world.resource::<ActionQueue>().is_empty(), hp == 10);
+        }
+    }
+
+    #[test]
+    fn channel_performs_its_action_at_the_end() {
+        let (mut world, player) = player_world();
+        let action = MoveAction::new(test_position(3, 2));
+        world
+            .resource_mut::<ActionQueue>()
+            .add_action(ChannelAction::new(2, action, InterruptTriggers::ALL));
+
+        for turn_number in 0..2 {
+            player_turn(&mut world, turn_number);
+            assert_eq!(world.get::<Position>(player), Some(&test_position(2, 2)));
+            assert!(!world.resource::<ActionQueue>().is_empty());
+        }
+
+        player_turn(&mut world, 2);
+        assert_eq!(world.get::<Position>(player), Some(&test_position(3, 2)));
+        assert!(world.resource::<ActionQueue>().is_empty());
+    }
+
+    #[test]
+    fn travel_takes_a_step_each_turn() {
+        let (mut world, player) = player_world();
+        let destination = test_position(5, 2);
+        world
+            .resource_mut::<ActionQueue>()
+            .add_action(TravelAction::new(destination, InterruptTriggers::ALL));
+
+        for (turn_number, x) in (3..=5).enumerate() {
+            player_turn(&mut world, turn_number as u32);
+            assert_eq!(world.get::<Position>(player), Some(&test_position(x, 2)));
+            assert_eq!(world.resource::<ActionQueue>().is_empty(), x == 5);
+        }
+    }
+
+    #[test]
+    fn messages_interrupt_multi_turn_actions() {
+        let (mut world, player) = player_world();
+        world.resource_mut::<ActionQueue>().add_action(RestAction::new(InterruptTriggers::ALL));
+
+        player_turn(&mut world, 0);
+        world.resource_mut::<MessageLog>().add("Something happened.");
+        player_turn(&mut world, 1);
+
+        // The interrupted turn takes no time and the player chooses again.
+        assert_eq!(world.get::<Health>(player).unwrap().current_hp, 8);
+        assert!(world.resource::<ActionQueue>().is_empty());
+        assert_eq!(
+            world.resource::<MessageLog>().last(),
+            Some(&ActionFailure::from(Interruption::Message).to_string())
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use crate::prelude::*;
+
+/// Takes actors on maps which aren't loaded out of the `TurnManager`,
+/// and puts them back in once their map is loaded again.
+pub fn suspend_unloaded_turns(
+    mut map_manager: MapManager,
+    mut turn_manager: ResMut<TurnManager>,
+    q_positions: Query<&Position>,
+    q_moved_actors: Query<
+        (Entity, &Position),
+        (
+            With<AIComponent>,
+            Or<(Added<AIComponent>, Changed<Position>)>,
+        ),
+    >,
+) {
+    // Only actors which just arrived or moved can have ended up off the loaded maps.
+    for (entity, position) in q_moved_actors.iter() {
+        if !map_manager.is_loaded(position.get_world_position()) {
+            turn_manager.suspend_entity(entity);
+        }
+    }
+
+    // However their map gets loaded, only the suspended actors are waiting on it.
+    let resumed: Vec<Entity> = turn_manager
+        .suspended()
+        .filter(|&entity| {
+            q_positions.get(entity).map_or(false, |position| {
+                map_manager.is_loaded(position.get_world_position())
+            })
+        })
+        .collect();
+    for entity in resumed {
+        turn_manager.resume_entity(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(world_position: WorldPosition) -> Position {
+        Position::new(
+            world_position,
+            LocalPosition::new(2, 2, MapLayer::Actors as u32),
+        )
+    }
+
+    #[test]
+    fn suspends_until_the_map_is_loaded() {
+        let mut world = test_world(UVec2::new(16, 16));
+        world.init_resource::<TurnManager>();
+        let (east, north) = (WorldPosition::new(1, 0, 0), WorldPosition::new(0, 1, 0));
+
+        let near = world.spawn((test_position(2, 2), AIComponent::aggressive())).id();
+        let far = world.spawn((position(east), AIComponent::aggressive())).id();
+        for entity in [near, far] {
+            world.resource_mut::<TurnManager>().add_entity(entity);
+        }
+
+        let mut stage = SystemStage::single(suspend_unloaded_turns);
+        stage.run(&mut world);
+        assert!(world.resource::<TurnManager>().contains(near));
+        assert!(world.resource::<TurnManager>().is_suspended(far));
+
+        // Moving off the loaded maps suspends too.
+        *world.get_mut::<Position>(near).unwrap() = position(north);
+        stage.run(&mut world);
+        assert!(world.resource::<TurnManager>().is_suspended(near));
+
+        world
+            .resource_mut::<MapManagerResource>()
+            .loaded_maps
+            .insert(east, test_map(UVec2::new(16, 16), east));
+        stage.run(&mut world);
+        assert!(world.resource::<TurnManager>().contains(far));
+        assert!(world.resource::<TurnManager>().is_suspended(near));
+    }
+}
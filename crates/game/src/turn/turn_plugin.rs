@@ -32,7 +32,19 @@ impl<T: StateNext> Plugin for ProccessTurnsPlugin<T> {
 
         app.add_system_set_to_stage(
             AtrlStage::ProcessTurns,
-            ConditionSet::new().run_in_state(self.state_running).with_system(perform_turns).into(),
+            ConditionSet::new()
+                .label("suspend_unloaded_turns")
+                .run_in_state(self.state_running)
+                .with_system(suspend_unloaded_turns)
+                .into(),
+        )
+        .add_system_set_to_stage(
+            AtrlStage::ProcessTurns,
+            ConditionSet::new()
+                .after("suspend_unloaded_turns")
+                .run_in_state(self.state_running)
+                .with_system(perform_turns)
+                .into(),
         );
     }
 }
@@ -14,6 +14,18 @@ mod ui {
         pub use main_menu_plugin::*;
     }
     pub use main_menu::*;
+    mod message_log {
+        mod systems {
+            mod setup_message_log;
+            pub use setup_message_log::*;
+            mod update_message_log;
+            pub use update_message_log::*;
+        }
+        pub use systems::*;
+        mod message_log_plugin;
+        pub use message_log_plugin::*;
+    }
+    pub use message_log::*;
     mod widgets {
         mod systems {
             mod menu_button_render;
@@ -0,0 +1,11 @@
+use crate::prelude::*;
+pub struct MessageLogPlugin<T> {
+    pub state_running: T,
+}
+impl<T: StateNext> Plugin for MessageLogPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_enter_system(self.state_running, setup_message_log)
+            .add_system(update_message_log.run_in_state(self.state_running))
+            .add_exit_system(self.state_running, despawn_with_recursive::<MessageLogText>);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::prelude::*;
+#[derive(Component)]
+pub struct MessageLogText;
+pub fn setup_message_log(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Name::new("MESSAGE_LOG"),
+        TextBundle::from_section("", TextStyle {
+            font: asset_server.load("fonts/JuliaMono/JuliaMono-Regular.ttf"),
+            font_size: 16.0,
+            color: Color::WHITE,
+        })
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(8.0),
+                bottom: Val::Px(8.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        MessageLogText,
+    ));
+}
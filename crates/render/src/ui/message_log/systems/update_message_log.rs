@@ -0,0 +1,20 @@
+use crate::prelude::*;
+/// How many of the newest messages are shown.
+pub const MESSAGES_SHOWN: usize = 5;
+pub fn update_message_log(
+    message_log: Option<Res<MessageLog>>,
+    mut q_text: Query<&mut Text, With<MessageLogText>>,
+) {
+    let Some(message_log) = message_log else { return; };
+    if !message_log.is_changed() {
+        return;
+    }
+
+    let skip = message_log.len().saturating_sub(MESSAGES_SHOWN);
+    let messages = message_log.iter().skip(skip).cloned().collect::<Vec<_>>().join("\n");
+    for mut text in q_text.iter_mut() {
+        if let Some(section) = text.sections.first_mut() {
+            section.value = messages.clone();
+        }
+    }
+}
@@ -4,6 +4,7 @@ pub struct UICamera;
 pub struct UiPlugin<T: StateNext> {
     pub state_asset_load: T,
     pub state_main_menu: T,
+    pub state_running: T,
 }
 impl<T: StateNext + std::default::Default> Plugin for UiPlugin<T> {
     fn build(&self, app: &mut App) {
@@ -13,6 +14,9 @@ impl<T: StateNext + std::default::Default> Plugin for UiPlugin<T> {
             .add_startup_system(spawn_component!((UICameraBundle::default(), UICamera)))
             .add_plugin(MainMenuPlugin {
                 state_main_menu: self.state_main_menu,
+            })
+            .add_plugin(MessageLogPlugin {
+                state_running: self.state_running,
             });
     }
 }
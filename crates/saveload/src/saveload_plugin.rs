@@ -5,15 +5,24 @@ impl Plugin for SaveLoadPlugin {
         app
             // -- Tags -- //
             //.register_type::<Player>()
+            .register_type::<PersistentId>()
             // -- AI -- //
             .register_type::<AIType>()
             .register_type::<AIComponent>()
             // -- Stats -- //
             .register_type::<Health>()
             .register_type::<Equipable>()
+            .register_type::<SpeedTarget>()
+            .register_type::<SpeedModifier>()
+            .register_type::<Speed>()
             // -- Map -- //
             .register_type::<VisionType>()
             .register_type::<Vision>()
+            .register_type::<Facing>()
+            .register_type::<CardinalDirection>()
+            .register_type::<Fov>()
+            .register_type::<LightFalloff>()
+            .register_type::<LightSource>()
             .register_type::<MovementType>()
             .register_type::<Movement>()
             //.register_type::<Map>()